@@ -1,25 +1,86 @@
-import { writeFileSync, existsSync, mkdirSync } from 'fs';
+import { readFileSync, writeFileSync, existsSync, mkdirSync, unlinkSync } from 'fs';
 import { dirname, join } from 'path';
 import { fileURLToPath } from 'url';
+import Parser from 'web-tree-sitter';
 
 const __dirname = dirname(fileURLToPath(import.meta.url));
 const parsersDir = join(__dirname, '..', 'public', 'parsers');
+const queriesDir = join(__dirname, '..', 'public', 'queries');
 
 // Ensure parsers directory exists
 if (!existsSync(parsersDir)) {
   mkdirSync(parsersDir, { recursive: true });
 }
 
-// Tree-sitter language WASM files from sourcegraph's pre-built packages on unpkg
+// Tree-sitter language WASM files from sourcegraph's pre-built packages on unpkg.
+// Pinned: QUERIES below must match the grammar versions this release bundles.
 const PARSERS = {
-  json: 'https://unpkg.com/tree-sitter-wasms@latest/out/tree-sitter-json.wasm',
-  rust: 'https://unpkg.com/tree-sitter-wasms@latest/out/tree-sitter-rust.wasm',
-  javascript: 'https://unpkg.com/tree-sitter-wasms@latest/out/tree-sitter-javascript.wasm',
-  typescript: 'https://unpkg.com/tree-sitter-wasms@latest/out/tree-sitter-typescript.wasm',
-  tsx: 'https://unpkg.com/tree-sitter-wasms@latest/out/tree-sitter-tsx.wasm',
-  python: 'https://unpkg.com/tree-sitter-wasms@latest/out/tree-sitter-python.wasm',
-  go: 'https://unpkg.com/tree-sitter-wasms@latest/out/tree-sitter-go.wasm',
-  ocaml: 'https://unpkg.com/tree-sitter-wasms@latest/out/tree-sitter-ocaml.wasm',
+  json: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-json.wasm',
+  rust: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-rust.wasm',
+  javascript: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-javascript.wasm',
+  typescript: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-typescript.wasm',
+  tsx: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-tsx.wasm',
+  python: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-python.wasm',
+  go: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-go.wasm',
+  ocaml: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-ocaml.wasm',
+  zig: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-zig.wasm',
+  elixir: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-elixir.wasm',
+  nix: 'https://unpkg.com/tree-sitter-wasms@0.1.12/out/tree-sitter-nix.wasm',
+  // Not in tree-sitter-wasms; each grammar package below ships its own build.
+  // web-tree-sitter 0.24 only loads ABI 13-14 grammars, so every one is
+  // pinned to a release built before the grammar moved to ABI 15.
+  haskell: 'https://unpkg.com/tree-sitter-haskell@0.23.1/tree-sitter-haskell.wasm',
+  gotmpl: 'https://unpkg.com/tree-sitter-go-template@0.0.1/tree-sitter-gotmpl.wasm',
+  // Block-level grammar only; inline markup (emphasis, links) stays unparsed
+  markdown: 'https://unpkg.com/@tree-sitter-grammars/tree-sitter-markdown@0.3.2/tree-sitter-markdown.wasm',
+  proto: 'https://unpkg.com/tree-sitter-proto@0.2.0/tree-sitter-proto.wasm',
+  // Lenient superset of JSON (comments, trailing commas), also used for JSONC
+  json5: 'https://unpkg.com/tree-sitter-json5@0.1.0/tree-sitter-json5.wasm',
+  r: 'https://unpkg.com/tree-sitter-r@1.1.0/tree-sitter-r.wasm',
+};
+
+// Query files shipped by each grammar's npm package, pinned to the version
+// compiled into the WASM loaded for that language. A query naming a node type
+// the loaded grammar lacks fails to compile ("Bad node name").
+const QUERIES = {
+  json: {
+    highlights: 'https://unpkg.com/tree-sitter-json@0.20.2/queries/highlights.scm',
+  },
+  rust: {
+    highlights: 'https://unpkg.com/tree-sitter-rust@0.20.4/queries/highlights.scm',
+    tags: 'https://unpkg.com/tree-sitter-rust@0.20.4/queries/tags.scm',
+  },
+  javascript: {
+    highlights: 'https://unpkg.com/tree-sitter-javascript@0.20.4/queries/highlights.scm',
+    tags: 'https://unpkg.com/tree-sitter-javascript@0.20.4/queries/tags.scm',
+  },
+  typescript: {
+    highlights: 'https://unpkg.com/tree-sitter-typescript@0.20.5/queries/highlights.scm',
+    tags: 'https://unpkg.com/tree-sitter-typescript@0.20.5/queries/tags.scm',
+  },
+  tsx: {
+    highlights: 'https://unpkg.com/tree-sitter-typescript@0.20.5/queries/highlights.scm',
+    tags: 'https://unpkg.com/tree-sitter-typescript@0.20.5/queries/tags.scm',
+  },
+  python: {
+    highlights: 'https://unpkg.com/tree-sitter-python@0.20.4/queries/highlights.scm',
+    tags: 'https://unpkg.com/tree-sitter-python@0.20.4/queries/tags.scm',
+  },
+  go: {
+    highlights: 'https://unpkg.com/tree-sitter-go@0.20.0/queries/highlights.scm',
+    tags: 'https://unpkg.com/tree-sitter-go@0.20.0/queries/tags.scm',
+  },
+  ocaml: {
+    highlights: 'https://unpkg.com/tree-sitter-ocaml@0.20.4/queries/highlights.scm',
+    tags: 'https://unpkg.com/tree-sitter-ocaml@0.20.4/queries/tags.scm',
+  },
+  haskell: {
+    highlights: 'https://unpkg.com/tree-sitter-haskell@0.23.1/queries/highlights.scm',
+  },
+  elixir: {
+    highlights: 'https://unpkg.com/tree-sitter-elixir@0.1.1/queries/highlights.scm',
+    tags: 'https://unpkg.com/tree-sitter-elixir@0.1.1/queries/tags.scm',
+  },
 };
 
 async function downloadFile(url, dest) {
@@ -35,7 +96,21 @@ async function downloadFile(url, dest) {
   console.log(`Saved to ${dest} (${(buffer.byteLength / 1024).toFixed(1)} KB)`);
 }
 
+// Grammars built for a newer ABI than this web-tree-sitter release supports
+// only fail once setLanguage() is called, so try that on every download.
+async function checkLoads(dest) {
+  const language = await Parser.Language.load(dest);
+  const probe = new Parser();
+  try {
+    probe.setLanguage(language);
+  } finally {
+    probe.delete();
+  }
+}
+
 async function main() {
+  await Parser.init();
+
   console.log('Downloading Tree-sitter language parsers from unpkg...\n');
 
   for (const [lang, url] of Object.entries(PARSERS)) {
@@ -44,6 +119,40 @@ async function main() {
       await downloadFile(url, dest);
     } catch (err) {
       console.error(`Failed to download ${lang} parser:`, err.message);
+      continue;
+    }
+    try {
+      await checkLoads(dest);
+    } catch (err) {
+      unlinkSync(dest);
+      console.error(`Downloaded ${lang} parser does not load, removed it:`, err.message);
+    }
+  }
+
+  console.log('\nDownloading Tree-sitter query files from unpkg...\n');
+
+  for (const [lang, queries] of Object.entries(QUERIES)) {
+    const langDir = join(queriesDir, lang);
+    if (!existsSync(langDir)) {
+      mkdirSync(langDir, { recursive: true });
+    }
+    const grammarPath = join(parsersDir, `tree-sitter-${lang}.wasm`);
+    const grammar = existsSync(grammarPath) ? await Parser.Language.load(grammarPath) : null;
+    for (const [name, url] of Object.entries(queries)) {
+      const dest = join(langDir, `${name}.scm`);
+      try {
+        await downloadFile(url, dest);
+      } catch (err) {
+        console.error(`Failed to download ${lang} ${name} query:`, err.message);
+        continue;
+      }
+      // Compiling catches node names the downloaded grammar doesn't have
+      try {
+        grammar?.query(readFileSync(dest, 'utf8')).delete();
+      } catch (err) {
+        unlinkSync(dest);
+        console.error(`Downloaded ${lang} ${name} query does not compile, removed it:`, err.message);
+      }
     }
   }
 
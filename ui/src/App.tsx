@@ -46,6 +46,14 @@ const SAMPLE_CODE: Record<string, string> = {
     "theme": "dark",
     "fontSize": 14
   }
+}`,
+  json5: `{
+  // Comments and trailing commas are allowed
+  compilerOptions: {
+    target: "ES2020",
+    strict: true,
+  },
+  include: ["src"],
 }`,
   rust: `fn main() {
     let message = "Hello, AST (Welcome to SpecTree)!";
@@ -111,6 +119,107 @@ let () =
 }
 
 export default Welcome;`,
+  haskell: `module Main where
+
+data Shape = Circle Double | Rect Double Double
+
+area :: Shape -> Double
+area (Circle r) = pi * r * r
+area (Rect w h) = w * h
+
+main :: IO ()
+main = do
+  let shapes = [Circle 1.0, Rect 2.0 3.0]
+  mapM_ (print . area) shapes`,
+  zig: `const std = @import("std");
+
+const Point = struct {
+    x: i32,
+    y: i32,
+};
+
+pub fn main() void {
+    const p = Point{ .x = 1, .y = 2 };
+    std.debug.print("({d}, {d})\\n", .{ p.x, p.y });
+}`,
+  elixir: `defmodule Greeter do
+  def greet(names) do
+    names
+    |> Enum.map(&String.capitalize/1)
+    |> Enum.each(fn name -> IO.puts("Hello, #{name}!") end)
+  end
+end
+
+Greeter.greet(["alice", "bob"])`,
+  gotmpl: `<h1>{{ .Title }}</h1>
+{{/* Render each item, or a placeholder when empty */}}
+<ul>
+{{ range $i, $item := .Items }}
+  <li class="{{ if eq $i 0 }}first{{ end }}">{{ $item.Name | html }}</li>
+{{ else }}
+  <li>No items</li>
+{{ end }}
+</ul>`,
+  markdown: `# SpecTree
+
+Explore **syntax trees** in the browser.
+
+- Pick a language
+- Edit the code
+- Inspect the AST
+
+\`\`\`python
+def greet(name):
+    return f"Hello, {name}!"
+\`\`\`
+`,
+  proto: `syntax = "proto3";
+
+package spectree.v1;
+
+import "google/protobuf/timestamp.proto";
+
+// A parsed source file
+message Document {
+  string path = 1;
+  Language language = 2;
+  repeated string tags = 3;
+  google.protobuf.Timestamp parsed_at = 4;
+}
+
+enum Language {
+  LANGUAGE_UNSPECIFIED = 0;
+  LANGUAGE_RUST = 1;
+  LANGUAGE_PYTHON = 2;
+}
+
+service DocumentService {
+  rpc Parse(Document) returns (Document);
+}`,
+  nix: `{ pkgs ? import <nixpkgs> { } }:
+
+let
+  # Tools available in the development shell
+  tools = with pkgs; [ nodejs wasm-pack ];
+in
+pkgs.mkShell {
+  name = "spectree-dev";
+  buildInputs = tools;
+  shellHook = ''
+    echo "Welcome to SpecTree"
+  '';
+}`,
+  r: `# Summarize measurements by group
+summarize <- function(df, column) {
+  if (!column %in% names(df)) {
+    stop("Unknown column: ", column)
+  }
+  df[[column]] |>
+    tapply(df$group, mean)
+}
+
+scores <- data.frame(group = c("a", "b", "a"), value = c(1.5, 2, 3.5))
+summarize(scores, "value")`,
 };
 
 // Simple OCaml mode for basic syntax highlighting
@@ -149,6 +258,7 @@ const ocamlLanguage = StreamLanguage.define({
 function getLanguageExtension(lang: string) {
   switch (lang) {
     case 'json':
+    case 'json5':
       return json();
     case 'rust':
       return rust();
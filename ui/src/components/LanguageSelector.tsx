@@ -12,6 +12,7 @@ import {
 // Language configuration with icons
 const LANGUAGES = [
     { value: 'json', label: 'JSON', icon: FileJson },
+    { value: 'json5', label: 'JSON5 / JSONC', icon: FileJson },
     { value: 'rust', label: 'Rust', icon: Braces },
     { value: 'javascript', label: 'JavaScript', icon: FileCode },
     { value: 'typescript', label: 'TypeScript', icon: FileType },
@@ -19,6 +20,14 @@ const LANGUAGES = [
     { value: 'python', label: 'Python', icon: Code2 },
     { value: 'go', label: 'Go', icon: Code2 },
     { value: 'ocaml', label: 'OCaml', icon: Code2 },
+    { value: 'haskell', label: 'Haskell', icon: Code2 },
+    { value: 'zig', label: 'Zig', icon: Code2 },
+    { value: 'elixir', label: 'Elixir', icon: Code2 },
+    { value: 'gotmpl', label: 'Go Template', icon: Code2 },
+    { value: 'markdown', label: 'Markdown', icon: Code2 },
+    { value: 'proto', label: 'Protocol Buffers', icon: Code2 },
+    { value: 'nix', label: 'Nix', icon: Code2 },
+    { value: 'r', label: 'R', icon: Code2 },
 ];
 
 interface LanguageSelectorProps {
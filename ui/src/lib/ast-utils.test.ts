@@ -0,0 +1,65 @@
+import { describe, expect, it } from 'vitest';
+import { parser } from './parser';
+import { firstNodeByKind, indentLevels, leafContexts, nthNodeByKind, reconstructSource } from './ast-utils';
+
+describe('indentLevels', () => {
+  it('follows nesting of blocks', async () => {
+    const code = [
+      'def f(x):',
+      '    if x:',
+      '        return 1',
+      '',
+      '    return 2',
+    ].join('\n');
+    const { ast } = await parser.parse(code, 'python');
+    const [def, ifLine, inner, blank, outer] = indentLevels(ast!, code);
+    expect(ifLine!).toBeGreaterThan(def!);
+    expect(inner!).toBeGreaterThan(ifLine!);
+    expect(blank).toBeNull();
+    expect(outer).toBe(ifLine);
+  });
+});
+
+describe('leafContexts', () => {
+  const code = '// one\n// two\nlet x;';
+
+  it('reports one entry per leaf by default', async () => {
+    const { ast } = await parser.parse(code, 'javascript');
+    const leaves = leafContexts(ast!, code);
+    expect(leaves.slice(0, 2).map((leaf) => leaf.text)).toEqual(['// one', '// two']);
+    expect(leaves[0].kindPath).toEqual(['program', 'comment']);
+  });
+
+  it('merges adjacent comment lines into one span with coalesce', async () => {
+    const { ast } = await parser.parse(code, 'javascript');
+    const leaves = leafContexts(ast!, code, true);
+    expect(leaves[0]).toMatchObject({ text: '// one\n// two', start: 0, end: 13 });
+    expect(leaves[1].text).toBe('let');
+  });
+});
+
+describe('nthNodeByKind', () => {
+  it('finds the nth node of a kind in document order', async () => {
+    const { ast } = await parser.parse('function a() {}\nfunction b() { function c() {} }\n', 'javascript');
+    const names = [0, 1, 2].map((n) => nthNodeByKind(ast!, 'function_declaration', n)?.children[1].text);
+    expect(names).toEqual(['a', 'b', 'c']);
+    expect(nthNodeByKind(ast!, 'function_declaration', 3)).toBeNull();
+  });
+});
+
+describe('reconstructSource', () => {
+  it('rebuilds the node span byte for byte, whitespace included', async () => {
+    const code = '  [1,\n   2]\n\n';
+    const { ast } = await parser.parse(code, 'json');
+    const array = firstNodeByKind(ast!, 'array')!;
+    expect(reconstructSource(array, code)).toEqual({ text: '[1,\n   2]', gaps: [] });
+  });
+
+  it('leaves out whitespace outside the node span', async () => {
+    const code = 'x = 1\n\n\n';
+    const { ast } = await parser.parse(code, 'python');
+    const { text } = reconstructSource(ast!, code);
+    expect(text).toBe(code.slice(ast!.start, ast!.end));
+    expect(code.startsWith(text)).toBe(true);
+  });
+});
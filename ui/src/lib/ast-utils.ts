@@ -0,0 +1,200 @@
+import type { AstNode } from './parser';
+
+// ============================================
+// Traversal
+// ============================================
+
+/**
+ * Visit every node in pre-order (document order). Return `false` from the
+ * visitor to stop the walk early.
+ */
+export function walkAst(
+  node: AstNode,
+  visit: (node: AstNode, depth: number) => boolean | void,
+  depth = 0,
+): boolean {
+  if (visit(node, depth) === false) return false;
+  for (const child of node.children) {
+    if (!walkAst(child, visit, depth + 1)) return false;
+  }
+  return true;
+}
+
+// ============================================
+// Lookup
+// ============================================
+
+/**
+ * Subtree of the first node (in document order) of the given kind, or null.
+ * Cheaper than collecting every match when only one is needed.
+ */
+export function firstNodeByKind(ast: AstNode, kind: string): AstNode | null {
+  let found: AstNode | null = null;
+  walkAst(ast, (node) => {
+    if (node.kind === kind) {
+      found = node;
+      return false;
+    }
+  });
+  return found;
+}
+
+/**
+ * Subtree of the `n`th (zero-based, document order) node of the given kind,
+ * or null when there are fewer than `n + 1`, e.g. "go to the third
+ * function". The walk stops at the match.
+ */
+export function nthNodeByKind(ast: AstNode, kind: string, n: number): AstNode | null {
+  let found: AstNode | null = null;
+  let remaining = n;
+  walkAst(ast, (node) => {
+    if (node.kind !== kind) return;
+    if (remaining-- === 0) {
+      found = node;
+      return false;
+    }
+  });
+  return found;
+}
+
+// ============================================
+// Source reconstruction
+// ============================================
+
+export interface SourceGap {
+  gapStart: number;
+  gapEnd: number;
+  droppedText: string;
+}
+
+export interface ReconstructedSource {
+  text: string;
+  /** Non-whitespace source text not covered by any leaf */
+  gaps: SourceGap[];
+}
+
+/**
+ * Reassemble source text from an AST's leaves. Whitespace between leaves is
+ * copied from `code`; anything else between them (e.g. operators dropped by
+ * the `namedOnly` option) is left out and reported as a gap, so
+ * transformation pipelines know exactly what a trimmed tree lost.
+ *
+ * Only `ast.start..ast.end` is rebuilt: whitespace outside that span, such
+ * as a trailing newline some grammars leave outside the root, is not.
+ */
+export function reconstructSource(ast: AstNode, code: string): ReconstructedSource {
+  let text = '';
+  const gaps: SourceGap[] = [];
+  let cursor = ast.start;
+
+  const fillGap = (until: number) => {
+    if (until <= cursor) return;
+    const between = code.slice(cursor, until);
+    if (/^\s*$/.test(between)) {
+      text += between;
+    } else {
+      gaps.push({ gapStart: cursor, gapEnd: until, droppedText: between });
+    }
+  };
+
+  walkAst(ast, (node) => {
+    if (node.children.length > 0) return;
+    fillGap(node.start);
+    text += node.text ?? code.slice(node.start, node.end);
+    cursor = Math.max(cursor, node.end);
+  });
+  fillGap(ast.end);
+
+  return { text, gaps };
+}
+
+// ============================================
+// Leaf contexts
+// ============================================
+
+export interface LeafContext {
+  text: string;
+  start: number;
+  end: number;
+  /** Kinds from the root down to the leaf itself */
+  kindPath: string[];
+}
+
+/**
+ * Every leaf token with its full structural context, e.g. for building
+ * labeled token sequences for code-model training. Paths can get long in
+ * deeply nested grammars; each leaf carries its own copy.
+ *
+ * With `coalesce`, consecutive leaves of the same kind (e.g. a run of line
+ * comments) merge into one span covering them and the text between, keeping
+ * the first leaf's path. Spans then no longer map one-to-one to nodes.
+ */
+export function leafContexts(ast: AstNode, code: string, coalesce = false): LeafContext[] {
+  const leaves: LeafContext[] = [];
+  const path: string[] = [];
+
+  const visit = (node: AstNode) => {
+    path.push(node.kind);
+    if (node.children.length === 0) {
+      leaves.push({
+        text: node.text ?? code.slice(node.start, node.end),
+        start: node.start,
+        end: node.end,
+        kindPath: [...path],
+      });
+    }
+    node.children.forEach(visit);
+    path.pop();
+  };
+
+  visit(ast);
+  return coalesce ? coalesceLeaves(leaves, code) : leaves;
+}
+
+function coalesceLeaves(leaves: LeafContext[], code: string): LeafContext[] {
+  const merged: LeafContext[] = [];
+  for (const leaf of leaves) {
+    const last = merged[merged.length - 1];
+    const kind = leaf.kindPath[leaf.kindPath.length - 1];
+    if (last && last.kindPath[last.kindPath.length - 1] === kind) {
+      last.end = leaf.end;
+      last.text = code.slice(last.start, last.end);
+    } else {
+      merged.push({ ...leaf });
+    }
+  }
+  return merged;
+}
+
+// ============================================
+// Indentation
+// ============================================
+
+/** Depth (root = 0) of the deepest node containing `offset` */
+function depthAt(ast: AstNode, offset: number): number {
+  let depth = 0;
+  let node: AstNode | undefined = ast;
+  while (node) {
+    node = node.children.find((child) => child.start <= offset && offset < child.end);
+    if (node) depth++;
+  }
+  return depth;
+}
+
+/**
+ * Per line, the tree depth at the line's first non-whitespace character, or
+ * null for blank lines. A heuristic for auto-indenters: depth grows with
+ * nesting much like indentation does, but grammars add wrapper nodes at
+ * different rates, so compare levels within a file rather than across
+ * languages.
+ */
+export function indentLevels(ast: AstNode, code: string): (number | null)[] {
+  const levels: (number | null)[] = [];
+  let lineStart = 0;
+  for (const line of code.split('\n')) {
+    const indent = line.search(/\S/);
+    levels.push(indent === -1 ? null : depthAt(ast, lineStart + indent));
+    lineStart += line.length + 1;
+  }
+  return levels;
+}
@@ -0,0 +1,55 @@
+import { describe, expect, it } from 'vitest';
+import { walkAst } from './ast-utils';
+import { findTodos, parseValidPrefix } from './diagnostics';
+
+describe('parseValidPrefix', () => {
+  it('returns the whole tree when there is no error', async () => {
+    const { ast, errorOffset } = await parseValidPrefix('foo(1);', 'javascript');
+    expect(errorOffset).toBeNull();
+    expect(ast.end).toBe(7);
+  });
+
+  it('drops a MISSING closer at the end of the input', async () => {
+    const { ast, errorOffset } = await parseValidPrefix('foo(1', 'javascript');
+    expect(errorOffset).toBe(5);
+    walkAst(ast, (node) => {
+      expect(node.start).toBeLessThan(5);
+      expect(node.end).toBeLessThanOrEqual(5);
+    });
+  });
+
+  it('cuts nodes straddling an error in the middle', async () => {
+    const code = 'let a = 1;\nlet b = ;\nlet c = 3;\n';
+    const { ast, errorOffset } = await parseValidPrefix(code, 'javascript');
+    expect(errorOffset).not.toBeNull();
+    expect(ast.end).toBe(errorOffset);
+    walkAst(ast, (node) => {
+      expect(node.end).toBeLessThanOrEqual(errorOffset!);
+      expect(node.kind).not.toBe('ERROR');
+    });
+  });
+});
+
+describe('findTodos', () => {
+  it('stops the range at a block comment terminator', async () => {
+    const code = 'let x; /* TODO: fix this */\n';
+    const [todo] = await findTodos(code, 'javascript');
+    expect(todo).toMatchObject({ marker: 'TODO', text: 'fix this', line: 0 });
+    expect(code.slice(todo.start, todo.end)).toBe('TODO: fix this');
+  });
+
+  it('reports each marker of a multi-line block comment on its own line', async () => {
+    const code = '/*\n * TODO one\n * HACK two */\nlet s = "TODO not me";\n';
+    const todos = await findTodos(code, 'javascript');
+    expect(todos.map((todo) => [todo.marker, code.slice(todo.start, todo.end), todo.line])).toEqual([
+      ['TODO', 'TODO one', 1],
+      ['HACK', 'HACK two', 2],
+    ]);
+  });
+
+  it('ends a line comment marker at the end of the line', async () => {
+    const code = '# FIXME: later  \nx = 1\n';
+    const [todo] = await findTodos(code, 'python');
+    expect(code.slice(todo.start, todo.end)).toBe('FIXME: later');
+  });
+});
@@ -0,0 +1,195 @@
+import type Parser from 'web-tree-sitter';
+import { parser, type AstNode, type ParseOptions } from './parser';
+import { toNodeSpan, type NodeSpan } from './navigation';
+import { kindsFor } from './kinds';
+
+// ============================================
+// Types
+// ============================================
+
+export interface UnclosedDelimiter {
+  /** The closing delimiter the parser had to insert, e.g. `}` */
+  expected: string;
+  /** Where the closer was expected */
+  offset: number;
+  position: { row: number; column: number };
+  /** The matching opener, when it's among the missing node's siblings */
+  opener: NodeSpan | null;
+}
+
+export interface TodoComment {
+  /** The marker that matched, e.g. `FIXME` */
+  marker: string;
+  /** The rest of the comment line after the marker */
+  text: string;
+  /**
+   * Range from the marker to the end of its line, or to a block comment's
+   * closing delimiter, trailing whitespace excluded
+   */
+  start: number;
+  end: number;
+  line: number;
+}
+
+export interface ValidPrefix {
+  /** The tree cut off where the first error starts */
+  ast: AstNode;
+  /** Start of the earliest ERROR or MISSING node, or null if there is none */
+  errorOffset: number | null;
+  errorPosition: { row: number; column: number } | null;
+}
+
+export const DEFAULT_TODO_MARKERS = ['TODO', 'FIXME', 'HACK', 'XXX'];
+
+// ============================================
+// Helpers
+// ============================================
+
+const OPENER_FOR: Record<string, string> = {
+  ')': '(',
+  ']': '[',
+  '}': '{',
+};
+
+function findOpener(missing: Parser.SyntaxNode, openerKind: string): Parser.SyntaxNode | null {
+  for (let sibling = missing.previousSibling; sibling; sibling = sibling.previousSibling) {
+    if (sibling.type === openerKind) return sibling;
+  }
+  return null;
+}
+
+function collectUnclosed(node: Parser.SyntaxNode, found: UnclosedDelimiter[]): void {
+  if (node.isMissing && Object.prototype.hasOwnProperty.call(OPENER_FOR, node.type)) {
+    const opener = findOpener(node, OPENER_FOR[node.type]);
+    found.push({
+      expected: node.type,
+      offset: node.startIndex,
+      position: node.startPosition,
+      opener: opener ? toNodeSpan(opener) : null,
+    });
+  }
+  for (let i = 0; i < node.childCount; i++) {
+    collectUnclosed(node.child(i)!, found);
+  }
+}
+
+function firstError(node: Parser.SyntaxNode): Parser.SyntaxNode | null {
+  if (node.type === 'ERROR' || node.isMissing) return node;
+  if (!node.hasError) return null;
+  for (let i = 0; i < node.childCount; i++) {
+    const found = firstError(node.child(i)!);
+    if (found) return found;
+  }
+  return null;
+}
+
+/**
+ * Keep what lies before `limit`; nodes straddling it end there. A node
+ * ending exactly at `limit` is still walked, since a zero-width MISSING
+ * node at `limit` can sit anywhere in its subtree.
+ */
+function prefixBefore(
+  node: AstNode,
+  limit: number,
+  limitPosition: { row: number; column: number },
+): AstNode {
+  if (node.end < limit) return node;
+  return {
+    ...node,
+    end: limit,
+    endPosition: limitPosition,
+    children: node.children
+      .filter((child) => child.start < limit)
+      .map((child) => prefixBefore(child, limit, limitPosition)),
+  };
+}
+
+function escapeRegExp(text: string): string {
+  return text.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+}
+
+// ============================================
+// Public API
+// ============================================
+
+/**
+ * Report closing brackets the parser had to insert during error recovery,
+ * with the opener each one belongs to. Uses the grammar rather than bracket
+ * counting, so brackets inside strings and comments are never miscounted.
+ */
+export async function findUnclosedDelimiters(
+  code: string,
+  language: string,
+): Promise<UnclosedDelimiter[]> {
+  return parser.withTree(code, language, (tree) => {
+    const found: UnclosedDelimiter[] = [];
+    collectUnclosed(tree.rootNode, found);
+    return found;
+  });
+}
+
+/**
+ * Find TODO-style markers in comments. Only comment nodes are scanned, so
+ * `"TODO"` inside a string literal is never reported. Markers match as
+ * whole words, case-sensitively.
+ */
+export async function findTodos(
+  code: string,
+  language: string,
+  markers: string[] = DEFAULT_TODO_MARKERS,
+): Promise<TodoComment[]> {
+  const { comments } = kindsFor(language);
+  if (markers.length === 0) return [];
+  // The text runs to the end of the line, stopping short of a block
+  // comment's terminator
+  const pattern = new RegExp(
+    `\\b(${markers.map(escapeRegExp).join('|')})\\b:?((?:(?!\\*/|\\*\\)|-\\}).)*)`,
+    'g',
+  );
+
+  return parser.withTree(code, language, (tree) => {
+    const todos: TodoComment[] = [];
+    for (const comment of tree.rootNode.descendantsOfType(comments)) {
+      const text = comment.text;
+      pattern.lastIndex = 0;
+      for (let m = pattern.exec(text); m; m = pattern.exec(text)) {
+        const start = comment.startIndex + m.index;
+        const lines = text.slice(0, m.index).split('\n');
+        todos.push({
+          marker: m[1],
+          text: m[2].trim(),
+          start,
+          end: start + m[0].trimEnd().length,
+          line: comment.startPosition.row + lines.length - 1,
+        });
+      }
+    }
+    return todos;
+  });
+}
+
+/**
+ * The part of the tree before the first syntax error, and where that error
+ * starts: "here's the valid prefix and where it broke", for REPLs and
+ * streaming input. This is a trim after a full parse, not early
+ * termination, so it costs as much as `parse()`. Nodes that straddle the
+ * error are kept, ending at it.
+ */
+export async function parseValidPrefix(
+  code: string,
+  language: string,
+  options: ParseOptions = {},
+): Promise<ValidPrefix> {
+  return parser.withTree(code, language, (tree) => {
+    const ast = parser.toAst(tree, options, code);
+    const error = firstError(tree.rootNode);
+    if (!error) {
+      return { ast, errorOffset: null, errorPosition: null };
+    }
+    return {
+      ast: prefixBefore(ast, error.startIndex, error.startPosition),
+      errorOffset: error.startIndex,
+      errorPosition: error.startPosition,
+    };
+  });
+}
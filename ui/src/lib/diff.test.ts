@@ -0,0 +1,76 @@
+import { describe, expect, it } from 'vitest';
+import { astsEqual, subtreeHash, treeEditScript } from './diff';
+import { parser } from './parser';
+
+describe('astsEqual', () => {
+  it('ignores whitespace and positions', async () => {
+    expect(await astsEqual('a+b*c', 'a  +  b * c', 'javascript')).toEqual({ equal: true });
+  });
+
+  it('reports the path to the first difference', async () => {
+    const result = await astsEqual('a + b', 'a - b', 'javascript');
+    expect(result).toEqual({
+      equal: false,
+      differingPath: ['program', 'expression_statement', 'binary_expression', '+'],
+    });
+  });
+
+  it('compares leaf text', async () => {
+    const result = await astsEqual('let x = 1;', 'let y = 1;', 'javascript');
+    expect(result.equal).toBe(false);
+    expect(result.differingPath?.slice(-1)).toEqual(['identifier']);
+  });
+
+  it('does not trust colliding subtree hashes', async () => {
+    // Two identifiers whose FNV-1a hashes collide, so both trees hash the same
+    const a = await parser.parse('vnedz;', 'javascript');
+    const b = await parser.parse('vyvfbb;', 'javascript');
+    expect(subtreeHash(a.ast!)).toBe(subtreeHash(b.ast!));
+
+    const result = await astsEqual('vnedz;', 'vyvfbb;', 'javascript');
+    expect(result).toEqual({
+      equal: false,
+      differingPath: ['program', 'expression_statement', 'identifier'],
+    });
+  });
+});
+
+describe('treeEditScript', () => {
+  it('is empty for identical trees', async () => {
+    expect(await treeEditScript('[1, 2]', '[1,2]', 'json')).toEqual([]);
+  });
+
+  it('updates a changed leaf in place', async () => {
+    expect(await treeEditScript('[1, 2]', '[1, 3]', 'json')).toEqual([
+      { op: 'update', kind: 'number', oldPath: '0.3', newPath: '0.3', text: '3' },
+    ]);
+  });
+
+  it('inserts and deletes unmatched subtrees', async () => {
+    const ops = await treeEditScript('[1, true]', '[1, {}]', 'json');
+    expect(ops.map(({ op, kind }) => [op, kind])).toEqual([
+      ['delete', 'true'],
+      ['insert', 'object'],
+    ]);
+  });
+
+  it('diffs thousands of siblings around a single change', async () => {
+    const items = Array.from({ length: 5000 }, (_, i) => String(i));
+    const before = `[${items.join(', ')}]`;
+    items[2500] = 'true';
+    const after = `[${items.join(', ')}]`;
+    const ops = await treeEditScript(before, after, 'json');
+    expect(ops.map(({ op, kind }) => [op, kind])).toEqual([
+      ['delete', 'number'],
+      ['insert', 'true'],
+    ]);
+  });
+
+  it('turns a reordered definition into a move', async () => {
+    const before = 'def a():\n    pass\n\ndef b():\n    return 1\n';
+    const after = 'def b():\n    return 1\n\ndef a():\n    pass\n';
+    const ops = await treeEditScript(before, after, 'python');
+    expect(ops).toHaveLength(1);
+    expect(ops[0]).toMatchObject({ op: 'move', kind: 'function_definition' });
+  });
+});
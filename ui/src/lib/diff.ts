@@ -0,0 +1,336 @@
+import { parser, type AstNode } from './parser';
+import type { ChangedRange } from './edits';
+
+// ============================================
+// Subtree hashing
+// ============================================
+
+/** 32-bit FNV-1a over a string's UTF-16 code units. */
+function fnv1a(input: string): number {
+  let hash = 0x811c9dc5;
+  for (let i = 0; i < input.length; i++) {
+    hash ^= input.charCodeAt(i);
+    hash = Math.imul(hash, 0x01000193);
+  }
+  return hash >>> 0;
+}
+
+function hashNode(node: AstNode, childHashes: string[]): string {
+  return fnv1a(`${node.kind}\0${node.text ?? ''}\0${childHashes.join(',')}`).toString(16);
+}
+
+/**
+ * Structural hash of a subtree: kinds, shape and leaf text, but not
+ * positions. Two subtrees that differ only in formatting hash the same.
+ */
+export function subtreeHash(node: AstNode): string {
+  return hashNode(node, node.children.map(subtreeHash));
+}
+
+/**
+ * A `subtreeHash()` that remembers every subtree it has hashed, so a diff
+ * visiting each level of a tree hashes each node once rather than once per
+ * ancestor.
+ */
+function memoizedHash(): (node: AstNode) => string {
+  const cache = new WeakMap<AstNode, string>();
+  const hash = (node: AstNode): string => {
+    let found = cache.get(node);
+    if (found === undefined) {
+      found = hashNode(node, node.children.map(hash));
+      cache.set(node, found);
+    }
+    return found;
+  };
+  return hash;
+}
+
+function rangeOf(node: AstNode): ChangedRange {
+  return {
+    start: node.start,
+    end: node.end,
+    startPosition: node.startPosition,
+    endPosition: node.endPosition,
+  };
+}
+
+// ============================================
+// Sequence alignment
+// ============================================
+
+/**
+ * Index pairs of a longest common subsequence of `a` and `b`. A common
+ * prefix and suffix are matched directly, so the quadratic table only
+ * covers the part in between: small for the usual localized edit.
+ */
+function lcsPairs(a: string[], b: string[]): [number, number][] {
+  let head = 0;
+  while (head < a.length && head < b.length && a[head] === b[head]) head++;
+  let tail = 0;
+  while (
+    tail < a.length - head &&
+    tail < b.length - head &&
+    a[a.length - 1 - tail] === b[b.length - 1 - tail]
+  ) {
+    tail++;
+  }
+
+  const pairs: [number, number][] = [];
+  for (let k = 0; k < head; k++) pairs.push([k, k]);
+
+  const midA = a.slice(head, a.length - tail);
+  const midB = b.slice(head, b.length - tail);
+  const table = Array.from({ length: midA.length + 1 }, () => new Uint32Array(midB.length + 1));
+  for (let i = midA.length - 1; i >= 0; i--) {
+    for (let j = midB.length - 1; j >= 0; j--) {
+      table[i][j] = midA[i] === midB[j]
+        ? table[i + 1][j + 1] + 1
+        : Math.max(table[i + 1][j], table[i][j + 1]);
+    }
+  }
+
+  let i = 0;
+  let j = 0;
+  while (i < midA.length && j < midB.length) {
+    if (midA[i] === midB[j]) {
+      pairs.push([head + i++, head + j++]);
+    } else if (table[i + 1][j] >= table[i][j + 1]) {
+      i++;
+    } else {
+      j++;
+    }
+  }
+
+  for (let k = tail; k > 0; k--) pairs.push([a.length - k, b.length - k]);
+  return pairs;
+}
+
+// ============================================
+// Public API
+// ============================================
+
+export interface ChangedNode {
+  kind: string;
+  change: 'modified' | 'added' | 'removed';
+  oldRange?: ChangedRange;
+  newRange?: ChangedRange;
+}
+
+/**
+ * Report which top-level constructs (functions, classes, ...) changed between
+ * two versions of a file. Subtrees are compared by structural hash, so pure
+ * reformatting is not reported. Unmatched subtrees between two unchanged ones
+ * pair up as `modified`; leftovers are `added` or `removed`.
+ */
+export async function changedNodes(
+  oldCode: string,
+  newCode: string,
+  language: string,
+): Promise<ChangedNode[]> {
+  const oldResult = await parser.parse(oldCode, language);
+  const newResult = await parser.parse(newCode, language);
+  if (!oldResult.ast || !newResult.ast) {
+    throw new Error(oldResult.error ?? newResult.error ?? 'Parse failed');
+  }
+
+  const oldNodes = oldResult.ast.children.filter((c) => c.isNamed);
+  const newNodes = newResult.ast.children.filter((c) => c.isNamed);
+  const hash = memoizedHash();
+  const pairs = lcsPairs(oldNodes.map(hash), newNodes.map(hash));
+
+  const changes: ChangedNode[] = [];
+  let i = 0;
+  let j = 0;
+  for (const [nextOld, nextNew] of [...pairs, [oldNodes.length, newNodes.length]]) {
+    while (i < nextOld && j < nextNew) {
+      const before = oldNodes[i++];
+      const after = newNodes[j++];
+      changes.push({
+        kind: after.kind,
+        change: 'modified',
+        oldRange: rangeOf(before),
+        newRange: rangeOf(after),
+      });
+    }
+    for (; i < nextOld; i++) {
+      changes.push({ kind: oldNodes[i].kind, change: 'removed', oldRange: rangeOf(oldNodes[i]) });
+    }
+    for (; j < nextNew; j++) {
+      changes.push({ kind: newNodes[j].kind, change: 'added', newRange: rangeOf(newNodes[j]) });
+    }
+    i = nextOld + 1;
+    j = nextNew + 1;
+  }
+
+  return changes;
+}
+
+export interface AstEquality {
+  equal: boolean;
+  /** Kinds from the root down to the first differing node, when unequal */
+  differingPath?: string[];
+}
+
+/**
+ * Path to the first node where the trees differ in kind, leaf text or child
+ * count, in document order; undefined when they match exactly.
+ */
+function firstDifference(a: AstNode, b: AstNode, path: string[]): string[] | undefined {
+  path.push(a.kind);
+  if (a.kind !== b.kind || a.text !== b.text || a.children.length !== b.children.length) {
+    return path;
+  }
+  for (let i = 0; i < a.children.length; i++) {
+    const found = firstDifference(a.children[i], b.children[i], path);
+    if (found) return found;
+  }
+  path.pop();
+  return undefined;
+}
+
+/**
+ * Whether two snippets have the same syntax, ignoring whitespace and
+ * positions: kinds, shape and leaf text (operators included) must match.
+ * Compared node by node rather than by subtree hash, so a hash collision
+ * can't make two different trees equal; on a mismatch the path to the first
+ * differing node is reported.
+ */
+export async function astsEqual(
+  codeA: string,
+  codeB: string,
+  language: string,
+): Promise<AstEquality> {
+  const resultA = await parser.parse(codeA, language);
+  const resultB = await parser.parse(codeB, language);
+  if (!resultA.ast || !resultB.ast) {
+    throw new Error(resultA.error ?? resultB.error ?? 'Parse failed');
+  }
+
+  const differingPath = firstDifference(resultA.ast, resultB.ast, []);
+  return differingPath ? { equal: false, differingPath } : { equal: true };
+}
+
+// ============================================
+// Edit scripts
+// ============================================
+
+export interface EditOperation {
+  op: 'insert' | 'delete' | 'update' | 'move';
+  kind: string;
+  /** Child-index path (e.g. `0.3.1`) in the old tree: delete, update, move */
+  oldPath?: string;
+  /** Child-index path in the new tree: insert, update, move */
+  newPath?: string;
+  /** New leaf text, for updates */
+  text?: string;
+}
+
+interface EditScript {
+  ops: EditOperation[];
+  /** Subtree hash of each insert/delete, for move detection */
+  hashes: Map<EditOperation, string>;
+  /** Memoized `subtreeHash()` shared across the whole diff */
+  hash: (node: AstNode) => string;
+}
+
+function childPath(path: string, index: number): string {
+  return path === '' ? String(index) : `${path}.${index}`;
+}
+
+function diffNodes(
+  before: AstNode,
+  after: AstNode,
+  oldPath: string,
+  newPath: string,
+  script: EditScript,
+): void {
+  if (before.children.length === 0 && after.children.length === 0) {
+    if (before.text !== after.text) {
+      script.ops.push({ op: 'update', kind: after.kind, oldPath, newPath, text: after.text });
+    }
+    return;
+  }
+
+  const oldHashes = before.children.map(script.hash);
+  const newHashes = after.children.map(script.hash);
+  const pairs = lcsPairs(oldHashes, newHashes);
+
+  let i = 0;
+  let j = 0;
+  for (const [nextOld, nextNew] of [...pairs, [before.children.length, after.children.length]]) {
+    // Unmatched children between two anchors pair up in order when their
+    // kinds agree; anything else is deleted or inserted
+    while (i < nextOld && j < nextNew && before.children[i].kind === after.children[j].kind) {
+      diffNodes(before.children[i], after.children[j], childPath(oldPath, i), childPath(newPath, j), script);
+      i++;
+      j++;
+    }
+    for (; i < nextOld; i++) {
+      const op: EditOperation = { op: 'delete', kind: before.children[i].kind, oldPath: childPath(oldPath, i) };
+      script.ops.push(op);
+      script.hashes.set(op, oldHashes[i]);
+    }
+    for (; j < nextNew; j++) {
+      const op: EditOperation = { op: 'insert', kind: after.children[j].kind, newPath: childPath(newPath, j) };
+      script.ops.push(op);
+      script.hashes.set(op, newHashes[j]);
+    }
+    i = nextOld + 1;
+    j = nextNew + 1;
+  }
+}
+
+/** Fold each delete/insert pair of identical subtrees into one move. */
+function detectMoves({ ops, hashes }: EditScript): EditOperation[] {
+  const deletes = new Map<string, EditOperation[]>();
+  for (const op of ops) {
+    const hash = hashes.get(op);
+    if (op.op !== 'delete' || hash === undefined) continue;
+    deletes.set(hash, [...(deletes.get(hash) ?? []), op]);
+  }
+
+  const moved = new Set<EditOperation>();
+  for (const op of ops) {
+    const hash = hashes.get(op);
+    if (op.op !== 'insert' || hash === undefined) continue;
+    const source = deletes.get(hash)?.shift();
+    if (!source) continue;
+    moved.add(source);
+    op.op = 'move';
+    op.oldPath = source.oldPath;
+  }
+
+  return ops.filter((op) => !moved.has(op));
+}
+
+/**
+ * Describe how to turn the old tree into the new one as subtree inserts,
+ * deletes, moves and leaf-text updates, e.g. to animate a structural diff.
+ *
+ * A heuristic, not a minimal script: children are aligned by a longest
+ * common subsequence of subtree hashes, unmatched children between
+ * alignments are compared pairwise when their kinds agree, and a deleted
+ * subtree reinserted unchanged elsewhere becomes a move. Operations are in
+ * document order; `oldPath`s address the old tree and `newPath`s the new
+ * one, in the `children` indices of the default AST.
+ */
+export async function treeEditScript(
+  oldCode: string,
+  newCode: string,
+  language: string,
+): Promise<EditOperation[]> {
+  const oldResult = await parser.parse(oldCode, language);
+  const newResult = await parser.parse(newCode, language);
+  if (!oldResult.ast || !newResult.ast) {
+    throw new Error(oldResult.error ?? newResult.error ?? 'Parse failed');
+  }
+
+  const script: EditScript = { ops: [], hashes: new Map(), hash: memoizedHash() };
+  if (oldResult.ast.kind !== newResult.ast.kind) {
+    script.ops.push({ op: 'delete', kind: oldResult.ast.kind, oldPath: '' });
+    script.ops.push({ op: 'insert', kind: newResult.ast.kind, newPath: '' });
+  } else {
+    diffNodes(oldResult.ast, newResult.ast, '', '', script);
+  }
+  return detectMoves(script);
+}
@@ -0,0 +1,113 @@
+import type Parser from 'web-tree-sitter';
+
+// ============================================
+// Text edits for incremental parsing
+// ============================================
+
+/** Row/column of a string index (0-indexed, columns in UTF-16 units). */
+export function pointAt(code: string, index: number): Parser.Point {
+  let row = 0;
+  let lineStart = 0;
+  for (let i = code.indexOf('\n'); i !== -1 && i < index; i = code.indexOf('\n', i + 1)) {
+    row++;
+    lineStart = i + 1;
+  }
+  return { row, column: index - lineStart };
+}
+
+/**
+ * Describe the change from `oldCode` to `newCode` as a single tree-sitter
+ * edit covering everything between their common prefix and suffix.
+ */
+export function computeEdit(oldCode: string, newCode: string): Parser.Edit {
+  const maxPrefix = Math.min(oldCode.length, newCode.length);
+  let prefix = 0;
+  while (prefix < maxPrefix && oldCode[prefix] === newCode[prefix]) prefix++;
+
+  const maxSuffix = maxPrefix - prefix;
+  let suffix = 0;
+  while (
+    suffix < maxSuffix &&
+    oldCode[oldCode.length - 1 - suffix] === newCode[newCode.length - 1 - suffix]
+  ) {
+    suffix++;
+  }
+
+  const oldEndIndex = oldCode.length - suffix;
+  const newEndIndex = newCode.length - suffix;
+  return {
+    startIndex: prefix,
+    oldEndIndex,
+    newEndIndex,
+    startPosition: pointAt(oldCode, prefix),
+    oldEndPosition: pointAt(oldCode, oldEndIndex),
+    newEndPosition: pointAt(newCode, newEndIndex),
+  };
+}
+
+// ============================================
+// Changed ranges
+// ============================================
+
+/** A source range that differs between two trees, in AstNode terms */
+export interface ChangedRange {
+  start: number;
+  end: number;
+  startPosition: { row: number; column: number };
+  endPosition: { row: number; column: number };
+}
+
+export function toChangedRange(range: Parser.Range): ChangedRange {
+  return {
+    start: range.startIndex,
+    end: range.endIndex,
+    startPosition: range.startPosition,
+    endPosition: range.endPosition,
+  };
+}
+
+/** The range an applied edit now covers in the new text */
+export function editedRange(edit: Parser.Edit): ChangedRange {
+  return {
+    start: edit.startIndex,
+    end: edit.newEndIndex,
+    startPosition: edit.startPosition,
+    endPosition: edit.newEndPosition,
+  };
+}
+
+// ============================================
+// Line ranges
+// ============================================
+
+/** Inclusive, 0-indexed line span */
+export interface LineRange {
+  startLine: number;
+  endLine: number;
+}
+
+/**
+ * Convert ranges to sorted, merged inclusive line spans for line-based
+ * editor decorations. A range ending at column 0 doesn't touch that line.
+ */
+export function toLineRanges(ranges: ChangedRange[]): LineRange[] {
+  const spans = ranges
+    .map((range) => ({
+      startLine: range.startPosition.row,
+      endLine: range.endPosition.column === 0 && range.endPosition.row > range.startPosition.row
+        ? range.endPosition.row - 1
+        : range.endPosition.row,
+    }))
+    .sort((a, b) => a.startLine - b.startLine);
+
+  const merged: LineRange[] = [];
+  for (const span of spans) {
+    const last = merged[merged.length - 1];
+    if (last && span.startLine <= last.endLine + 1) {
+      last.endLine = Math.max(last.endLine, span.endLine);
+    } else {
+      merged.push({ ...span });
+    }
+  }
+  return merged;
+}
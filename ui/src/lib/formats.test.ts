@@ -0,0 +1,102 @@
+import { describe, expect, it } from 'vitest';
+import { parser, type AstNode } from './parser';
+import { normalizeAstForSnapshot, renderTree, toFlatAst, withLabels } from './formats';
+
+describe('normalizeAstForSnapshot', () => {
+  it('keeps kind, structure and leaf text', async () => {
+    const { ast } = await parser.parse('[1, 2]', 'json', { namedOnly: true });
+    expect(normalizeAstForSnapshot(ast!)).toEqual({
+      kind: 'document',
+      children: [
+        {
+          kind: 'array',
+          children: [
+            { kind: 'number', text: '1', children: [] },
+            { kind: 'number', text: '2', children: [] },
+          ],
+        },
+      ],
+    });
+  });
+
+  it('ignores positions', async () => {
+    const a = await parser.parse('[1,2]', 'json');
+    const b = await parser.parse('[ 1 ,\n  2 ]', 'json');
+    expect(normalizeAstForSnapshot(a.ast!)).toEqual(normalizeAstForSnapshot(b.ast!));
+  });
+});
+
+describe('renderTree', () => {
+  it('draws branches with kinds and quoted leaf text', async () => {
+    const { ast } = await parser.parse('[1, "two"]', 'json', { namedOnly: true });
+    const lines = renderTree(ast!).split('\n');
+    expect(lines[0]).toBe('document');
+    expect(lines[1]).toBe('└── array');
+    expect(lines[2]).toBe('    ├── number "1"');
+    expect(lines[3]).toMatch(/^    └── string/);
+  });
+
+  it('continues vertical lines past non-last children', async () => {
+    const { ast } = await parser.parse('[[1], 2]', 'json', { namedOnly: true });
+    const rendered = renderTree(ast!);
+    expect(rendered).toContain('    ├── array');
+    expect(rendered).toContain('    │   └── number "1"');
+    expect(rendered).toContain('    └── number "2"');
+  });
+
+  it('truncates long leaf text', async () => {
+    const { ast } = await parser.parse('12345678901234567890', 'json');
+    expect(renderTree(ast!, 5)).toContain('number "12345…"');
+  });
+});
+
+describe('toFlatAst', () => {
+  const code = 'fn main() { let x = [1, 2]; }';
+
+  it('puts every child before its parent in post-order', async () => {
+    const { ast } = await parser.parse(code, 'rust');
+    const nodes = toFlatAst(ast!, 'post');
+    expect(nodes[nodes.length - 1]).toMatchObject({ kind: 'source_file', parentIndex: -1 });
+    nodes.forEach((node, i) => {
+      if (node.parentIndex === -1) return;
+      expect(node.parentIndex).toBeGreaterThan(i);
+      const parent = nodes[node.parentIndex];
+      expect(parent.start).toBeLessThanOrEqual(node.start);
+      expect(parent.end).toBeGreaterThanOrEqual(node.end);
+    });
+  });
+
+  it('has the same nodes and parents as pre-order', async () => {
+    const { ast } = await parser.parse(code, 'rust');
+    const edges = (nodes: ReturnType<typeof toFlatAst>) =>
+      nodes
+        .map((node) => {
+          const parent = nodes[node.parentIndex];
+          return `${node.kind}@${node.start} < ${parent ? `${parent.kind}@${parent.start}` : '-'}`;
+        })
+        .sort();
+    expect(edges(toFlatAst(ast!, 'post'))).toEqual(edges(toFlatAst(ast!, 'pre')));
+  });
+});
+
+describe('withLabels', () => {
+  const leaf = (text: string): AstNode => ({
+    kind: 'string_fragment',
+    start: 0,
+    end: text.length,
+    startPosition: { row: 0, column: 0 },
+    endPosition: { row: 0, column: text.length },
+    text,
+    isNamed: true,
+    children: [],
+  });
+
+  it('truncates long leaf text', () => {
+    expect(withLabels(leaf('abcdefgh'), 4).label).toBe('string_fragment "abcd…"');
+  });
+
+  it('never splits a surrogate pair', () => {
+    expect(withLabels(leaf('ab😀cd'), 3).label).toBe('string_fragment "ab…"');
+    expect(withLabels(leaf('ab😀cd'), 4).label).toBe('string_fragment "ab😀…"');
+  });
+});
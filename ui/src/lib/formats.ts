@@ -0,0 +1,316 @@
+import type { AstNode } from './parser';
+import { roundToCharBoundary } from './text';
+
+// ============================================
+// Line-map output
+// ============================================
+
+/** An AST node with byte offsets replaced by 0-indexed line numbers */
+export interface LineMapNode {
+  kind: string;
+  startLine: number;
+  endLine: number;
+  text?: string;
+  isNamed: boolean;
+  children: LineMapNode[];
+}
+
+/**
+ * Strip offsets and columns, keeping only line numbers. Trees of code that
+ * differs only in intra-line whitespace or indentation compare equal.
+ */
+export function toLineMapAst(node: AstNode): LineMapNode {
+  return {
+    kind: node.kind,
+    startLine: node.startPosition.row,
+    endLine: node.endPosition.row,
+    text: node.text,
+    isNamed: node.isNamed,
+    children: node.children.map(toLineMapAst),
+  };
+}
+
+// ============================================
+// Labeled output
+// ============================================
+
+export interface LabeledNode extends Omit<AstNode, 'children'> {
+  /** `kind` for inner nodes, `kind "text"` (truncated) for leaves */
+  label: string;
+  children: LabeledNode[];
+}
+
+/**
+ * Attach a display label to every node. Leaf text is truncated to
+ * `maxPreview` characters so huge literals don't bloat the payload, one
+ * fewer when the cut would split a surrogate pair.
+ */
+export function withLabels(node: AstNode, maxPreview = 20): LabeledNode {
+  let label = node.kind;
+  if (node.children.length === 0 && node.text !== undefined) {
+    const preview = node.text.length > maxPreview
+      ? node.text.slice(0, roundToCharBoundary(node.text, maxPreview)) + '…'
+      : node.text;
+    label = `${node.kind} ${JSON.stringify(preview)}`;
+  }
+
+  return {
+    ...node,
+    label,
+    children: node.children.map((child) => withLabels(child, maxPreview)),
+  };
+}
+
+// ============================================
+// Snapshot output
+// ============================================
+
+export interface SnapshotNode {
+  kind: string;
+  text?: string;
+  children: SnapshotNode[];
+}
+
+/**
+ * Reduce an AST to kind, structure, and leaf text for snapshot fixtures.
+ * Positions and flags are dropped, so snapshots stay stable across minor
+ * grammar bumps that only shift ranges.
+ */
+export function normalizeAstForSnapshot(node: AstNode): SnapshotNode {
+  const normalized: SnapshotNode = {
+    kind: node.kind,
+    children: node.children.map(normalizeAstForSnapshot),
+  };
+  if (node.text !== undefined) {
+    normalized.text = node.text;
+  }
+  return normalized;
+}
+
+// ============================================
+// Minimal output
+// ============================================
+
+export interface MinimalNode {
+  kind: string;
+  start: number;
+  end: number;
+  children: MinimalNode[];
+}
+
+/**
+ * Keep only kind, offsets and structure. Serialized, this is roughly a third
+ * the size of the default AST JSON (no positions, flags or text), for
+ * embeds that already hold the source and slice text out themselves.
+ */
+export function toMinimalAst(node: AstNode): MinimalNode {
+  return {
+    kind: node.kind,
+    start: node.start,
+    end: node.end,
+    children: node.children.map(toMinimalAst),
+  };
+}
+
+// ============================================
+// Columnar output
+// ============================================
+
+/**
+ * Struct-of-arrays layout: index `i` across every column describes node `i`
+ * in pre-order. The root's parent is -1.
+ */
+export interface ColumnarAst {
+  count: number;
+  kinds: string[];
+  starts: Uint32Array;
+  ends: Uint32Array;
+  parents: Int32Array;
+  named: Uint8Array;
+  texts: (string | null)[];
+}
+
+function countNodes(node: AstNode): number {
+  return node.children.reduce((sum, child) => sum + countNodes(child), 1);
+}
+
+/** Flatten an AST into columns for fast typed-array access. */
+export function toColumnarAst(ast: AstNode): ColumnarAst {
+  const count = countNodes(ast);
+  const columns: ColumnarAst = {
+    count,
+    kinds: [],
+    starts: new Uint32Array(count),
+    ends: new Uint32Array(count),
+    parents: new Int32Array(count),
+    named: new Uint8Array(count),
+    texts: [],
+  };
+
+  let next = 0;
+  const visit = (node: AstNode, parent: number) => {
+    const index = next++;
+    columns.kinds.push(node.kind);
+    columns.starts[index] = node.start;
+    columns.ends[index] = node.end;
+    columns.parents[index] = parent;
+    columns.named[index] = node.isNamed ? 1 : 0;
+    columns.texts.push(node.text ?? null);
+    for (const child of node.children) visit(child, index);
+  };
+
+  visit(ast, -1);
+  return columns;
+}
+
+// ============================================
+// Binary output
+// ============================================
+
+// Sentinel for "no node" in parent/first-child slots
+const NO_NODE = 0xffffffff;
+const RECORD_SIZE = 20;
+
+/**
+ * Encode an AST as fixed-width little-endian records for decoding with a
+ * `DataView`, without allocating an object per node.
+ *
+ * Layout:
+ * - Header: `nodeCount: u32`, `kindCount: u32`.
+ * - Kind dictionary: `kindCount` entries of `byteLength: u16` followed by
+ *   that many UTF-8 bytes. Padded with zeros to a multiple of 4 bytes.
+ * - `nodeCount` records of 20 bytes, in pre-order (root first):
+ *   `kind: u16` (dictionary index), `flags: u16` (bit 0: named),
+ *   `start: u32`, `end: u32`, `parent: u32`, `firstChild: u32`.
+ *   Missing parents/children are `0xFFFFFFFF`. A node's next sibling is
+ *   the next record with the same parent.
+ *
+ * Text is omitted; slice it from the source using `start`/`end`.
+ */
+export function toBinaryAst(ast: AstNode): ArrayBuffer {
+  const nodes: { node: AstNode; parent: number }[] = [];
+  const collect = (node: AstNode, parent: number) => {
+    const index = nodes.push({ node, parent }) - 1;
+    for (const child of node.children) collect(child, index);
+  };
+  collect(ast, NO_NODE);
+
+  const kindIds = new Map<string, number>();
+  for (const { node } of nodes) {
+    if (!kindIds.has(node.kind)) kindIds.set(node.kind, kindIds.size);
+  }
+  const encoder = new TextEncoder();
+  const kindBytes = [...kindIds.keys()].map((kind) => encoder.encode(kind));
+  const dictionarySize = kindBytes.reduce((sum, bytes) => sum + 2 + bytes.length, 0);
+  const recordsOffset = 8 + Math.ceil(dictionarySize / 4) * 4;
+
+  const buffer = new ArrayBuffer(recordsOffset + nodes.length * RECORD_SIZE);
+  const view = new DataView(buffer);
+  const bytes = new Uint8Array(buffer);
+  view.setUint32(0, nodes.length, true);
+  view.setUint32(4, kindIds.size, true);
+
+  let offset = 8;
+  for (const kind of kindBytes) {
+    view.setUint16(offset, kind.length, true);
+    bytes.set(kind, offset + 2);
+    offset += 2 + kind.length;
+  }
+
+  nodes.forEach(({ node, parent }, index) => {
+    const record = recordsOffset + index * RECORD_SIZE;
+    view.setUint16(record, kindIds.get(node.kind)!, true);
+    view.setUint16(record + 2, node.isNamed ? 1 : 0, true);
+    view.setUint32(record + 4, node.start, true);
+    view.setUint32(record + 8, node.end, true);
+    view.setUint32(record + 12, parent, true);
+    view.setUint32(record + 16, node.children.length > 0 ? index + 1 : NO_NODE, true);
+  });
+
+  return buffer;
+}
+
+// ============================================
+// Flat output
+// ============================================
+
+export interface FlatNode {
+  kind: string;
+  start: number;
+  end: number;
+  text?: string;
+  isNamed: boolean;
+  /** Index of the parent in the same array; -1 for the root */
+  parentIndex: number;
+}
+
+/**
+ * Flatten an AST into an array of nodes linked by `parentIndex`. In
+ * `'post'` order every child comes before its parent (root last), which
+ * suits bottom-up evaluation; `'pre'` puts parents first.
+ */
+export function toFlatAst(ast: AstNode, order: 'pre' | 'post' = 'pre'): FlatNode[] {
+  const nodes: FlatNode[] = [];
+
+  const visit = (node: AstNode, parentIndex: number): number => {
+    const flat: FlatNode = {
+      kind: node.kind,
+      start: node.start,
+      end: node.end,
+      text: node.text,
+      isNamed: node.isNamed,
+      parentIndex,
+    };
+
+    if (order === 'pre') {
+      const index = nodes.push(flat) - 1;
+      for (const child of node.children) visit(child, index);
+      return index;
+    }
+
+    // Children are emitted first, so their parent index is patched afterwards
+    const childIndices = node.children.map((child) => visit(child, -1));
+    const index = nodes.push(flat) - 1;
+    for (const childIndex of childIndices) nodes[childIndex].parentIndex = index;
+    return index;
+  };
+
+  visit(ast, -1);
+  return nodes;
+}
+
+// ============================================
+// ASCII tree output
+// ============================================
+
+function quotePreview(text: string, max: number): string {
+  return JSON.stringify(text.length > max ? text.slice(0, max) + '…' : text);
+}
+
+/**
+ * Render an AST as an indented text tree (like `tree-sitter parse`) for
+ * terminals and logs. Leaves show their text, truncated to `maxPreview`.
+ */
+export function renderTree(ast: AstNode, maxPreview = 40): string {
+  const lines: string[] = [];
+
+  const visit = (node: AstNode, prefix: string, connector: string, childPrefix: string) => {
+    const text = node.children.length === 0 && node.text !== undefined
+      ? ` ${quotePreview(node.text, maxPreview)}`
+      : '';
+    lines.push(`${prefix}${connector}${node.kind}${text}`);
+
+    node.children.forEach((child, i) => {
+      const isLast = i === node.children.length - 1;
+      visit(
+        child,
+        prefix + childPrefix,
+        isLast ? '└── ' : '├── ',
+        isLast ? '    ' : '│   ',
+      );
+    });
+  };
+
+  visit(ast, '', '', '');
+  return lines.join('\n');
+}
@@ -0,0 +1,55 @@
+import { parser } from './parser';
+
+// ============================================
+// Grammar metadata
+// ============================================
+
+/**
+ * All field names defined by a language's grammar (e.g. `name`,
+ * `parameters`, `body`). tree-sitter doesn't expose which fields belong to
+ * which kind, but the full set is enough for field-aware query completion.
+ */
+export async function listFields(language: string): Promise<string[]> {
+  const lang = await parser.loadLanguage(language);
+  const fields: string[] = [];
+  // Field ids start at 1; 0 means "no field"
+  for (let id = 1; id <= lang.fieldCount; id++) {
+    const name = lang.fieldNameForId(id);
+    if (name) fields.push(name);
+  }
+  return fields;
+}
+
+export interface GrammarSummary {
+  totalKinds: number;
+  namedKinds: number;
+  anonymousKinds: number;
+  fieldCount: number;
+  /** tree-sitter ABI version the grammar was generated with */
+  abiVersion: number;
+}
+
+/**
+ * Size of a language's grammar: how many distinct visible node kinds it can
+ * produce (hidden `_rules` excluded), split by named/anonymous, plus its
+ * field count and ABI version. Enough for a "supported languages" table.
+ */
+export async function grammarSummary(language: string): Promise<GrammarSummary> {
+  const lang = await parser.loadLanguage(language);
+  // Aliases can give several symbol ids the same kind; count kinds once
+  const named = new Set<string>();
+  const anonymous = new Set<string>();
+  for (let id = 0; id < lang.nodeTypeCount; id++) {
+    const kind = lang.nodeTypeForId(id);
+    if (!kind || !lang.nodeTypeIsVisible(id)) continue;
+    (lang.nodeTypeIsNamed(id) ? named : anonymous).add(kind);
+  }
+
+  return {
+    totalKinds: named.size + anonymous.size,
+    namedKinds: named.size,
+    anonymousKinds: anonymous.size,
+    fieldCount: lang.fieldCount,
+    abiVersion: lang.version,
+  };
+}
@@ -0,0 +1,41 @@
+import { describe, expect, it } from 'vitest';
+import { guessLanguage } from './guess';
+
+describe('guessLanguage', () => {
+  it('ranks a clean snippet in its own language first', async () => {
+    const guesses = await guessLanguage('fn main() {\n    let x: u32 = 1;\n}\n');
+    expect(guesses[0]).toMatchObject({ language: 'rust', errorCount: 0 });
+  });
+
+  it('still ranks a slightly broken Python snippet as Python', async () => {
+    const code = [
+      'import os',
+      '',
+      'def load(path):',
+      '    with open(path) as f:',
+      '        return f.read(',
+      '',
+      'class Config:',
+      '    def __init__(self, name):',
+      '        self.name = name',
+      '',
+    ].join('\n');
+    const guesses = await guessLanguage(code);
+    expect(guesses[0].language).toBe('python');
+    expect(guesses[0].errorCount).toBeGreaterThan(0);
+  });
+
+  it('still ranks a slightly broken JavaScript snippet as JavaScript', async () => {
+    const code = 'const items = [1, 2, 3];\nfunction total(xs) {\n  return xs.reduce((a, b) => a + b, 0;\n}\n';
+    const guesses = await guessLanguage(code);
+    // TypeScript is a superset, so its grammars may tie with JavaScript
+    expect(['javascript', 'typescript', 'tsx']).toContain(guesses[0].language);
+    expect(guesses[0].errorCount).toBeGreaterThan(0);
+  });
+
+  it('ranks catch-all grammars last', async () => {
+    const guesses = await guessLanguage('x = 1 +\n');
+    const tail = guesses.slice(-2).map((guess) => guess.language).sort();
+    expect(tail).toEqual(['gotmpl', 'markdown']);
+  });
+});
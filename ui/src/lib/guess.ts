@@ -0,0 +1,81 @@
+import type Parser from 'web-tree-sitter';
+import { parser } from './parser';
+
+// ============================================
+// Types
+// ============================================
+
+export interface LanguageGuess {
+  language: string;
+  /** ERROR and MISSING nodes in the parse */
+  errorCount: number;
+  namedCount: number;
+  nodeCount: number;
+}
+
+// Only this much of a snippet is tried against every grammar
+const GUESS_MAX_LENGTH = 16 * 1024;
+
+// Grammars that accept any text without errors (prose, template text), so
+// they'd outrank a real language whenever its snippet has a single typo
+const CATCH_ALL_LANGUAGES = new Set(['gotmpl', 'markdown']);
+
+// ============================================
+// Helpers
+// ============================================
+
+function scoreTree(tree: Parser.Tree): Omit<LanguageGuess, 'language'> {
+  const score = { errorCount: 0, namedCount: 0, nodeCount: 0 };
+  const cursor = tree.walk();
+  try {
+    for (;;) {
+      score.nodeCount++;
+      if (cursor.nodeIsNamed) score.namedCount++;
+      if (cursor.nodeType === 'ERROR' || cursor.nodeIsMissing) score.errorCount++;
+
+      if (cursor.gotoFirstChild() || cursor.gotoNextSibling()) continue;
+      let climbing = true;
+      while (climbing) {
+        if (!cursor.gotoParent()) return score;
+        climbing = !cursor.gotoNextSibling();
+      }
+    }
+  } finally {
+    cursor.delete();
+  }
+}
+
+// ============================================
+// Public API
+// ============================================
+
+/**
+ * Parse a snippet with every supported grammar and rank them: fewest errors
+ * first, then most named nodes, then most nodes overall. A heuristic for
+ * pasted code with no filename; short or polyglot snippets (`[1, 2]` is
+ * valid in half the languages) can easily rank the wrong one first. Only
+ * the first 16K characters are tried. Grammars that fail to load are left
+ * out. Markdown and Go templates parse anything cleanly, so they only rank
+ * as a fallback, after every other grammar.
+ */
+export async function guessLanguage(code: string): Promise<LanguageGuess[]> {
+  const sample = code.slice(0, GUESS_MAX_LENGTH);
+  const guesses: LanguageGuess[] = [];
+
+  for (const language of parser.getSupportedLanguages()) {
+    try {
+      const score = await parser.withTree(sample, language, scoreTree);
+      guesses.push({ language, ...score });
+    } catch {
+      // Grammar unavailable (e.g. WASM not downloaded); skip it
+    }
+  }
+
+  const catchAll = (guess: LanguageGuess) => (CATCH_ALL_LANGUAGES.has(guess.language) ? 1 : 0);
+  return guesses.sort((a, b) =>
+    catchAll(a) - catchAll(b) ||
+    a.errorCount - b.errorCount ||
+    b.namedCount - a.namedCount ||
+    b.nodeCount - a.nodeCount,
+  );
+}
@@ -0,0 +1,128 @@
+import { describe, expect, it } from 'vitest';
+import { parser } from './parser';
+import { computeEdit } from './edits';
+import { IncrementalParser, type ChunkReader } from './incremental';
+
+function chunked(code: string, size: number, offsets: number[] = []): ChunkReader {
+  return (index) => {
+    offsets.push(index);
+    return code.slice(index, index + size) || null;
+  };
+}
+
+describe('IncrementalParser.parseWithCallback', () => {
+  it('parses text served in chunks like the whole string', async () => {
+    const code = 'fn main() {\n    let total = add(1, 2);\n}\n';
+    const incremental = new IncrementalParser('rust');
+    try {
+      const offsets: number[] = [];
+      const result = await incremental.parseWithCallback(chunked(code, 4, offsets));
+      const direct = await parser.parse(code, 'rust');
+      expect(result.success).toBe(true);
+      expect(result.ast).toEqual(direct.ast);
+      expect(offsets.length).toBeGreaterThan(1);
+    } finally {
+      incremental.delete();
+    }
+  });
+
+  it('reuses the tree after a reported edit', async () => {
+    const before = 'fn main() {\n    let total = add(1, 2);\n}\n';
+    const after = 'fn main() {\n    let total = add(1, 2, 3);\n}\n';
+    const incremental = new IncrementalParser('rust');
+    try {
+      await incremental.parseWithCallback(chunked(before, 8));
+      incremental.edit(computeEdit(before, after));
+      const result = await incremental.parseWithCallback(chunked(after, 8));
+      const direct = await parser.parse(after, 'rust');
+      expect(result.ast).toEqual(direct.ast);
+    } finally {
+      incremental.delete();
+    }
+  });
+});
+
+describe('IncrementalParser.changedLineRanges', () => {
+  it('reports the line an edit touched', async () => {
+    const lines = Array.from({ length: 15 }, (_, i) => `let v${i} = ${i};`);
+    const before = lines.join('\n');
+    // Line 10, 0-indexed as 9
+    lines[9] = 'let v9 = 99;';
+    const after = lines.join('\n');
+
+    const incremental = new IncrementalParser('javascript');
+    try {
+      await incremental.parse(before);
+      expect(incremental.changedLineRanges()).toEqual([{ startLine: 0, endLine: 14 }]);
+
+      await incremental.parse(after);
+      const ranges = incremental.changedLineRanges();
+      expect(ranges.some((r) => r.startLine <= 9 && 9 <= r.endLine)).toBe(true);
+      expect(ranges.some((r) => r.startLine <= 0)).toBe(false);
+    } finally {
+      incremental.delete();
+    }
+  });
+});
+
+describe('IncrementalParser.parseWithChangeFlags', () => {
+  it('flags only the nodes of the edited function', async () => {
+    const before = 'function a() { return 1; }\nfunction b() { return 2; }\n';
+    const after = 'function a() { return 1; }\nfunction b() { return 20; }\n';
+
+    const incremental = new IncrementalParser('javascript');
+    try {
+      const first = await incremental.parseWithChangeFlags(before);
+      expect(first.ast?.changed).toBe(true);
+
+      const { ast } = await incremental.parseWithChangeFlags(after);
+      const [a, b] = ast!.children;
+      const flags = (node: typeof a): boolean[] => [node.changed, ...node.children.flatMap(flags)];
+      expect(flags(a).every((changed) => !changed)).toBe(true);
+      expect(b.changed).toBe(true);
+      expect(ast!.changed).toBe(true);
+      expect(b.children.find((child) => child.kind === 'statement_block')?.changed).toBe(true);
+    } finally {
+      incremental.delete();
+    }
+  });
+});
+
+describe('IncrementalParser.parseStep', () => {
+  // Big enough that a one-microsecond budget can't cover it in one step
+  const big = `[${Array.from({ length: 20000 }, (_, i) => `{"k${i}": [${i}, true, null]}`).join(', ')}]`;
+
+  it('resumes a parse over several steps and matches a one-shot parse', async () => {
+    const incremental = new IncrementalParser('json');
+    try {
+      let halted = 0;
+      let result = await incremental.parseStep(big, 1);
+      while (!result.done && halted < 100000) {
+        halted++;
+        result = await incremental.parseStep(big, 1);
+      }
+      expect(halted).toBeGreaterThan(1);
+      expect(result.success).toBe(true);
+
+      const direct = await parser.parse(big, 'json');
+      expect(result.ast).toEqual(direct.ast);
+    } finally {
+      incremental.delete();
+    }
+  });
+
+  it('abandons a halted parse when the code changes', async () => {
+    const incremental = new IncrementalParser('json');
+    try {
+      const first = await incremental.parseStep(big, 1);
+      expect(first).toMatchObject({ success: true, done: false });
+
+      const other = '{"a": [1, 2]}';
+      const result = await incremental.parseStep(other, 0);
+      expect(result.done).toBe(true);
+      expect(result.ast).toEqual((await parser.parse(other, 'json')).ast);
+    } finally {
+      incremental.delete();
+    }
+  });
+});
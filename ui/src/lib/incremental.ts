@@ -0,0 +1,323 @@
+import Parser from 'web-tree-sitter';
+import { parser, type AstNode, type ParseOptions, type ParseResult } from './parser';
+import {
+  computeEdit,
+  editedRange,
+  toChangedRange,
+  toLineRanges,
+  type ChangedRange,
+  type LineRange,
+} from './edits';
+
+// ============================================
+// Types
+// ============================================
+
+/**
+ * Supplies source text on demand: returns the chunk starting at `index`
+ * (UTF-16 offset), or null/'' at end of input. Same contract as
+ * web-tree-sitter's `Parser.Input`.
+ */
+export type ChunkReader = (index: number, position?: Parser.Point) => string | null;
+
+export interface ChangeFlaggedNode extends Omit<AstNode, 'children'> {
+  /** Whether the node overlaps a range that changed in the last parse */
+  changed: boolean;
+  children: ChangeFlaggedNode[];
+}
+
+export interface ChangeFlaggedResult extends Omit<ParseResult, 'ast'> {
+  ast?: ChangeFlaggedNode;
+}
+
+export interface ParseStepResult extends ParseResult {
+  /** False when the time budget ran out; call `parseStep()` again */
+  done: boolean;
+}
+
+// ============================================
+// Helpers
+// ============================================
+
+function overlaps(node: AstNode, range: ChangedRange): boolean {
+  // Deletions leave an empty range; it touches the nodes around it
+  if (range.start === range.end) {
+    return node.start <= range.start && range.start <= node.end;
+  }
+  return node.start < range.end && range.start < node.end;
+}
+
+// What web-tree-sitter throws when the C parser returns no tree, which with
+// a timeout set means it stopped early and can be resumed
+const HALTED_PARSE_MESSAGE = 'Parsing failed';
+
+function isHaltedParse(error: unknown): boolean {
+  return error instanceof Error && error.message === HALTED_PARSE_MESSAGE;
+}
+
+function flagChanges(node: AstNode, ranges: ChangedRange[]): ChangeFlaggedNode {
+  const changed = ranges.some((range) => overlaps(node, range));
+  return {
+    ...node,
+    changed,
+    // Nothing below an unchanged node can overlap a change
+    children: node.children.map((child) => flagChanges(child, changed ? ranges : [])),
+  };
+}
+
+// ============================================
+// Incremental parser
+// ============================================
+
+/**
+ * A parser that keeps its previous tree between calls, so each reparse only
+ * revisits the regions that changed. Owns its own tree-sitter parser and
+ * tree; call `delete()` when done to free them.
+ */
+export class IncrementalParser {
+  private tsParser: Parser | null = null;
+  private tree: Parser.Tree | null = null;
+  // Last parsed text, or null when it came from a chunk reader
+  private source: string | null = null;
+  // Edits applied since the last parse, and what that parse changed
+  private pendingEdits: ChangedRange[] = [];
+  private lastChanges: ChangedRange[] = [];
+  // Set when edits since the last parse were reported through `edit()`
+  private editsReported = false;
+  // Text of a `parseStep()` parse halted by its time budget, and whether
+  // that parse reuses the previous tree
+  private stepping: string | null = null;
+  private stepReusesTree = false;
+
+  constructor(readonly language: string) {}
+
+  private async ready(): Promise<Parser> {
+    if (this.tsParser) return this.tsParser;
+    const lang = await parser.loadLanguage(this.language);
+    this.tsParser = new Parser();
+    this.tsParser.setLanguage(lang);
+    return this.tsParser;
+  }
+
+  private result(options: ParseOptions): ParseResult {
+    const ast = parser.toAst(this.tree!, options, this.source ?? undefined);
+    return { success: true, ast, language: this.language };
+  }
+
+  private failure(error: unknown): ParseResult {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : String(error),
+      language: this.language,
+    };
+  }
+
+  private applyEdit(edit: Parser.Edit): void {
+    if (!this.tree) return;
+    this.tree.edit(edit);
+    this.pendingEdits.push(editedRange(edit));
+  }
+
+  /** Drop a halted `parseStep()` parse so the next parse starts fresh. */
+  private abandonStep(tsParser: Parser): void {
+    if (this.stepping === null) return;
+    tsParser.reset();
+    this.stepping = null;
+  }
+
+  private replaceTree(tree: Parser.Tree, reused: boolean): void {
+    if (this.tree && reused) {
+      const structural = this.tree.getChangedRanges(tree).map(toChangedRange);
+      this.lastChanges = [...this.pendingEdits, ...structural];
+    } else {
+      // Nothing to compare against: the whole document is new
+      const root = tree.rootNode;
+      this.lastChanges = [{
+        start: 0,
+        end: root.endIndex,
+        startPosition: { row: 0, column: 0 },
+        endPosition: root.endPosition,
+      }];
+    }
+    this.pendingEdits = [];
+    this.editsReported = false;
+    this.tree?.delete();
+    this.tree = tree;
+  }
+
+  private async reparseTree(code: string): Promise<void> {
+    const tsParser = await this.ready();
+    this.abandonStep(tsParser);
+    if (this.source !== null && !this.editsReported) {
+      this.applyEdit(computeEdit(this.source, code));
+    }
+    const oldTree = this.source !== null ? this.tree : null;
+    this.replaceTree(tsParser.parse(code, oldTree), oldTree !== null);
+    this.source = code;
+  }
+
+  /**
+   * Parse `code`, reusing the previous tree. The edit from the last parsed
+   * text is computed automatically, unless edits were reported through
+   * `edit()`.
+   */
+  async parse(code: string, options: ParseOptions = {}): Promise<ParseResult> {
+    try {
+      await this.reparseTree(code);
+      return this.result(options);
+    } catch (error) {
+      return this.failure(error);
+    }
+  }
+
+  /**
+   * Like `parse()`, but skip converting the tree to an AST, so a keystroke
+   * costs work proportional to the edit. Read what changed from
+   * `changedRanges()` and fetch the AST with `currentAst()` when needed.
+   */
+  async update(code: string): Promise<ParseResult> {
+    try {
+      await this.reparseTree(code);
+      return { success: true, language: this.language };
+    } catch (error) {
+      return this.failure(error);
+    }
+  }
+
+  /** Convert the tree from the last finished parse. */
+  currentAst(options: ParseOptions = {}): ParseResult {
+    if (!this.tree) return this.failure('Nothing has been parsed yet');
+    try {
+      return this.result(options);
+    } catch (error) {
+      return this.failure(error);
+    }
+  }
+
+  /**
+   * Like `parse()`, but flag every node that overlaps a region changed since
+   * the previous parse (see `changedLineRanges()`), e.g. to gray out
+   * untouched code. On the first parse everything counts as changed.
+   */
+  async parseWithChangeFlags(
+    code: string,
+    options: ParseOptions = {},
+  ): Promise<ChangeFlaggedResult> {
+    const { ast, ...result } = await this.parse(code, options);
+    if (!ast) return result;
+    return { ...result, ast: flagChanges(ast, this.lastChanges) };
+  }
+
+  /**
+   * Record an edit made to the parsed text. Needed before the next
+   * `parseWithCallback()` so the old tree can be reused. Before `parse()`,
+   * reported edits replace the diff against the previous text, saving a
+   * scan of the document; they must then describe every change.
+   */
+  edit(edit: Parser.Edit): void {
+    this.applyEdit(edit);
+    this.editsReported = true;
+  }
+
+  /**
+   * Parse text served in chunks by `read`, e.g. from a rope or gap buffer,
+   * without joining it into one string. Edits since the last callback parse
+   * must have been reported through `edit()`.
+   */
+  async parseWithCallback(read: ChunkReader, options: ParseOptions = {}): Promise<ParseResult> {
+    try {
+      const tsParser = await this.ready();
+      this.abandonStep(tsParser);
+      const oldTree = this.source === null ? this.tree : null;
+      this.replaceTree(tsParser.parse(read, oldTree), oldTree !== null);
+      this.source = null;
+      return this.result(options);
+    } catch (error) {
+      return this.failure(error);
+    }
+  }
+
+  /**
+   * Parse `code` for at most `budgetMicros` per call, so a main-thread caller
+   * can spread a huge parse over several frames. Returns `done: false` when
+   * time runs out; calling again with the same `code` resumes where the
+   * last step stopped. Once done, the result is the same as `parse()`'s.
+   *
+   * There is no partial tree between steps: results from earlier parses
+   * (and `changedLineRanges()`) stay as they were until the step that
+   * finishes. Passing different `code`, or calling `parse()` or
+   * `parseWithCallback()`, abandons the halted parse.
+   */
+  async parseStep(
+    code: string,
+    budgetMicros: number,
+    options: ParseOptions = {},
+  ): Promise<ParseStepResult> {
+    try {
+      const tsParser = await this.ready();
+      if (this.stepping !== code) {
+        this.abandonStep(tsParser);
+        this.stepReusesTree = this.source !== null;
+        if (this.source !== null) {
+          if (!this.editsReported) this.applyEdit(computeEdit(this.source, code));
+          // The retained tree now matches `code`, even before it's reparsed
+          this.source = code;
+          this.editsReported = false;
+        }
+        this.stepping = code;
+      }
+
+      let tree: Parser.Tree | null = null;
+      tsParser.setTimeoutMicros(budgetMicros);
+      try {
+        tree = tsParser.parse(code, this.stepReusesTree ? this.tree : null);
+      } catch (error) {
+        // Out of time: tree-sitter keeps its progress for the next call.
+        // Anything else is a real failure, and resuming would loop forever.
+        if (!(budgetMicros > 0 && isHaltedParse(error))) {
+          this.abandonStep(tsParser);
+          throw error;
+        }
+      } finally {
+        tsParser.setTimeoutMicros(0);
+      }
+      if (!tree) {
+        return { success: true, done: false, language: this.language };
+      }
+
+      this.stepping = null;
+      this.replaceTree(tree, this.stepReusesTree);
+      this.source = code;
+      return { ...this.result(options), done: true };
+    } catch (error) {
+      return { ...this.failure(error), done: true };
+    }
+  }
+
+  /**
+   * Lines touched by the last parse: the edited text plus every range whose
+   * syntax changed (e.g. a closing quote restructuring later lines). After
+   * a fresh parse, the whole document. Suited to line-based re-highlighting.
+   */
+  changedLineRanges(): LineRange[] {
+    return toLineRanges(this.lastChanges);
+  }
+
+  /** The ranges behind `changedLineRanges()`, as offsets and positions. */
+  changedRanges(): ChangedRange[] {
+    return [...this.lastChanges];
+  }
+
+  /** Free the parser and the retained tree. */
+  delete(): void {
+    this.tree?.delete();
+    this.tree = null;
+    this.tsParser?.delete();
+    this.tsParser = null;
+    this.source = null;
+    this.pendingEdits = [];
+    this.lastChanges = [];
+    this.editsReported = false;
+    this.stepping = null;
+  }
+}
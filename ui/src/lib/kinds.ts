@@ -0,0 +1,309 @@
+import { resolveLanguage } from './languages';
+
+// ============================================
+// Per-language node kind tables
+// ============================================
+
+// Grammar-specific node kinds the analysis helpers look for. Languages
+// without an entry fall back to DEFAULT_KINDS.
+
+export interface LanguageKinds {
+  strings: string[];
+  comments: string[];
+  /** Import/dependency statements, one node per imported module */
+  imports: string[];
+  /** Named declarations (functions, types, ...) with a `name` field */
+  declarations: string[];
+  /** Function-like nodes, including closures and lambdas */
+  functions: string[];
+  /** Decision points counted by complexity(), e.g. `if`, `&&`, case arms */
+  decisions: string[];
+  /** Identifier tokens: variable, field, type and module names */
+  identifiers: string[];
+}
+
+const DEFAULT_KINDS: LanguageKinds = {
+  strings: ['string'],
+  comments: ['comment'],
+  imports: ['import_statement', 'import_declaration'],
+  declarations: [
+    'function_declaration',
+    'function_definition',
+    'class_declaration',
+    'class_definition',
+  ],
+  functions: [
+    'function_declaration',
+    'function_definition',
+    'method_declaration',
+    'method_definition',
+  ],
+  decisions: [
+    'if_statement',
+    'while_statement',
+    'for_statement',
+    'case',
+    'catch_clause',
+    '&&',
+    '||',
+  ],
+  identifiers: ['identifier'],
+};
+
+const JS_KINDS: LanguageKinds = {
+  strings: ['string', 'template_string', 'regex'],
+  comments: ['comment'],
+  imports: ['import_statement'],
+  declarations: [
+    'function_declaration',
+    'generator_function_declaration',
+    'class_declaration',
+    'method_definition',
+  ],
+  functions: [
+    'function_declaration',
+    'generator_function_declaration',
+    'function_expression',
+    'arrow_function',
+    'method_definition',
+  ],
+  decisions: [
+    'if_statement',
+    'while_statement',
+    'do_statement',
+    'for_statement',
+    'for_in_statement',
+    'switch_case',
+    'catch_clause',
+    'ternary_expression',
+    '&&',
+    '||',
+    '??',
+  ],
+  identifiers: ['identifier', 'property_identifier', 'shorthand_property_identifier'],
+};
+
+const TS_KINDS: LanguageKinds = {
+  ...JS_KINDS,
+  declarations: [
+    ...JS_KINDS.declarations,
+    'abstract_class_declaration',
+    'interface_declaration',
+    'type_alias_declaration',
+    'enum_declaration',
+  ],
+  identifiers: [...JS_KINDS.identifiers, 'type_identifier'],
+};
+
+const LANGUAGE_KINDS: Record<string, LanguageKinds> = {
+  json: {
+    strings: ['string'],
+    comments: ['comment'],
+    imports: [],
+    declarations: [],
+    functions: [],
+    decisions: [],
+    identifiers: [],
+  },
+  json5: {
+    strings: ['string'],
+    comments: ['comment'],
+    imports: [],
+    declarations: [],
+    functions: [],
+    decisions: [],
+    identifiers: ['identifier'],
+  },
+  rust: {
+    strings: ['string_literal', 'raw_string_literal', 'char_literal'],
+    comments: ['line_comment', 'block_comment'],
+    imports: ['use_declaration', 'extern_crate_declaration'],
+    declarations: [
+      'function_item',
+      'struct_item',
+      'enum_item',
+      'union_item',
+      'trait_item',
+      'type_item',
+      'const_item',
+      'static_item',
+      'mod_item',
+      'macro_definition',
+    ],
+    functions: ['function_item', 'closure_expression'],
+    decisions: [
+      'if_expression',
+      'while_expression',
+      'for_expression',
+      'match_arm',
+      '&&',
+      '||',
+    ],
+    identifiers: ['identifier', 'field_identifier', 'type_identifier'],
+  },
+  javascript: JS_KINDS,
+  typescript: TS_KINDS,
+  tsx: TS_KINDS,
+  python: {
+    strings: ['string'],
+    comments: ['comment'],
+    imports: ['import_statement', 'import_from_statement', 'future_import_statement'],
+    declarations: ['function_definition', 'class_definition'],
+    functions: ['function_definition', 'lambda'],
+    decisions: [
+      'if_statement',
+      'elif_clause',
+      'while_statement',
+      'for_statement',
+      'except_clause',
+      'case_clause',
+      'conditional_expression',
+      'if_clause',
+      'and',
+      'or',
+    ],
+    identifiers: ['identifier'],
+  },
+  go: {
+    strings: ['interpreted_string_literal', 'raw_string_literal', 'rune_literal'],
+    comments: ['comment'],
+    imports: ['import_spec'],
+    declarations: ['function_declaration', 'method_declaration', 'type_spec', 'const_spec', 'var_spec'],
+    functions: ['function_declaration', 'method_declaration', 'func_literal'],
+    decisions: [
+      'if_statement',
+      'for_statement',
+      'expression_case',
+      'type_case',
+      'communication_case',
+      '&&',
+      '||',
+    ],
+    identifiers: ['identifier', 'field_identifier', 'type_identifier', 'package_identifier'],
+  },
+  ocaml: {
+    strings: ['string', 'character'],
+    comments: ['comment'],
+    imports: ['open_module'],
+    declarations: ['type_binding', 'module_binding'],
+    functions: ['let_binding', 'fun_expression'],
+    decisions: ['if_expression', 'while_expression', 'for_expression', 'match_case', '&&', '||'],
+    identifiers: ['value_name', 'module_name', 'type_constructor', 'constructor_name', 'field_name'],
+  },
+  haskell: {
+    strings: ['string', 'char'],
+    comments: ['comment', 'haddock'],
+    imports: ['import'],
+    declarations: ['function', 'data_type', 'newtype', 'class'],
+    functions: ['function'],
+    decisions: ['conditional', 'alternative', 'guards'],
+    identifiers: ['variable', 'constructor', 'name'],
+  },
+  zig: {
+    strings: ['STRINGLITERALSINGLE', 'LINESTRING', 'CHAR_LITERAL'],
+    comments: ['line_comment', 'doc_comment', 'container_doc_comment'],
+    imports: [],
+    declarations: [],
+    functions: [],
+    decisions: [],
+    identifiers: ['IDENTIFIER'],
+  },
+  elixir: {
+    strings: ['string', 'charlist', 'sigil'],
+    comments: ['comment'],
+    imports: [],
+    declarations: [],
+    functions: [],
+    decisions: [],
+    identifiers: ['identifier', 'alias'],
+  },
+  gotmpl: {
+    strings: ['interpreted_string_literal', 'raw_string_literal', 'rune_literal'],
+    comments: ['comment'],
+    imports: [],
+    declarations: [],
+    functions: [],
+    decisions: [],
+    identifiers: ['identifier', 'field_identifier', 'variable'],
+  },
+  markdown: {
+    strings: ['fenced_code_block', 'indented_code_block'],
+    comments: ['html_block'],
+    imports: [],
+    declarations: [],
+    functions: [],
+    decisions: [],
+    identifiers: [],
+  },
+  proto: {
+    strings: ['string'],
+    comments: ['comment'],
+    imports: ['import'],
+    // Grammar has no `name` fields on messages/enums/services
+    declarations: [],
+    functions: [],
+    decisions: [],
+    identifiers: ['identifier'],
+  },
+  nix: {
+    strings: ['string_expression', 'indented_string_expression'],
+    comments: ['comment'],
+    imports: [],
+    // Bindings have an `attrpath` rather than a `name` field
+    declarations: [],
+    functions: ['function_expression'],
+    decisions: ['if_expression', 'assert_expression'],
+    identifiers: ['identifier'],
+  },
+  r: {
+    strings: ['string'],
+    comments: ['comment'],
+    // `library()` and `source()` are ordinary calls
+    imports: [],
+    declarations: [],
+    functions: ['function_definition'],
+    decisions: ['if_statement', 'for_statement', 'while_statement', 'repeat_statement'],
+    identifiers: ['identifier'],
+  },
+};
+
+export function kindsFor(language: string): LanguageKinds {
+  return LANGUAGE_KINDS[resolveLanguage(language) ?? language] ?? DEFAULT_KINDS;
+}
+
+// ============================================
+// Kind categories
+// ============================================
+
+export type KindCategory =
+  | 'keyword'
+  | 'identifier'
+  | 'literal'
+  | 'operator'
+  | 'comment'
+  | 'punctuation'
+  | 'structural';
+
+const PUNCTUATION = new Set(['(', ')', '[', ']', '{', '}', ',', ';', ':', '.', '::', '"', "'", '`']);
+const LITERAL_PATTERN = /(literal|string|integer|float|number|char|boolean|^true$|^false$|^null$|^nil$|^none$)/i;
+const IDENTIFIER_PATTERN = /(identifier|^name$|^variable$|^constructor$)/i;
+
+/**
+ * Coarse, language-agnostic category for coloring a tree view. Anonymous
+ * tokens are keywords when they're word-like, punctuation for brackets and
+ * separators, and operators otherwise; named kinds are matched against the
+ * language's comment/string tables and a few naming heuristics, falling back
+ * to `structural`.
+ */
+export function classifyKind(language: string, kind: string, isNamed = true): KindCategory {
+  if (!isNamed) {
+    if (/^[A-Za-z_][A-Za-z0-9_]*$/.test(kind)) return 'keyword';
+    return PUNCTUATION.has(kind) ? 'punctuation' : 'operator';
+  }
+
+  const kinds = kindsFor(language);
+  if (kinds.comments.includes(kind)) return 'comment';
+  if (kinds.strings.includes(kind) || LITERAL_PATTERN.test(kind)) return 'literal';
+  if (IDENTIFIER_PATTERN.test(kind)) return 'identifier';
+  return 'structural';
+}
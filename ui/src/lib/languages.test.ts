@@ -0,0 +1,212 @@
+import { describe, expect, it } from 'vitest';
+import { parser } from './parser';
+import { walkAst } from './ast-utils';
+import { detectLanguage, resolveLanguage } from './languages';
+
+/** Parse `code`, assert it has no syntax errors, and list the kinds it uses. */
+async function cleanKinds(code: string, language: string): Promise<Set<string>> {
+  const result = await parser.parse(code, language);
+  expect(result.success).toBe(true);
+  const hasError = await parser.withTree(code, language, (tree) => tree.rootNode.hasError);
+  expect(hasError).toBe(false);
+
+  const kinds = new Set<string>();
+  walkAst(result.ast!, (node) => {
+    kinds.add(node.kind);
+  });
+  return kinds;
+}
+
+describe('haskell', () => {
+  it('parses a module with a function and a data declaration', async () => {
+    const code = [
+      'module Main where',
+      '',
+      'data Shape = Circle Double | Square Double',
+      '',
+      'area :: Shape -> Double',
+      'area (Circle r) = pi * r * r',
+      'area (Square s) = s * s',
+      '',
+    ].join('\n');
+    const kinds = await cleanKinds(code, 'hs');
+    expect(kinds).toContain('data_type');
+    expect(kinds).toContain('signature');
+    expect(kinds).toContain('function');
+  });
+
+  it('ends a layout block at the first dedented line', async () => {
+    const code = [
+      'describe x = case x of',
+      '  1 -> "one"',
+      '  _ -> "many"',
+      'answer = 42',
+      '',
+    ].join('\n');
+    await cleanKinds(code, 'haskell');
+    const { ast } = await parser.parse(code, 'haskell');
+
+    let alternatives = 0;
+    walkAst(ast!, (node) => {
+      if (node.kind === 'alternative') alternatives++;
+    });
+    expect(alternatives).toBe(2);
+    // `answer` is a top-level binding, not a third case alternative
+    const answer = code.indexOf('answer');
+    let topLevel = false;
+    walkAst(ast!, (node, depth) => {
+      if (node.kind === 'function' && node.start === answer) topLevel = depth <= 2;
+    });
+    expect(topLevel).toBe(true);
+  });
+});
+
+describe('zig', () => {
+  it('parses a pub fn and a struct definition', async () => {
+    const code = [
+      'const Point = struct {',
+      '    x: i32,',
+      '    y: i32,',
+      '};',
+      '',
+      'pub fn origin() Point {',
+      '    return Point{ .x = 0, .y = 0 };',
+      '}',
+      '',
+    ].join('\n');
+    const kinds = await cleanKinds(code, 'zig');
+    expect(kinds).toContain('FnProto');
+    expect(kinds).toContain('ContainerDecl');
+  });
+});
+
+describe('elixir', () => {
+  it('parses a defmodule with a def and a pipe', async () => {
+    const code = [
+      'defmodule Greeter do',
+      '  def greet(name) do',
+      '    name |> String.trim() |> IO.puts()',
+      '  end',
+      'end',
+      '',
+    ].join('\n');
+    const kinds = await cleanKinds(code, 'exs');
+    expect(kinds).toContain('call');
+    expect(kinds).toContain('do_block');
+    expect(kinds).toContain('binary_operator');
+    expect(kinds).toContain('|>');
+  });
+});
+
+describe('gotmpl', () => {
+  it('parses a field action and a range block', async () => {
+    const code = '<h1>{{ .Title }}</h1>\n{{ range .Items }}<li>{{ .Name }}</li>{{ end }}\n';
+    const kinds = await cleanKinds(code, 'gohtml');
+    expect(kinds).toContain('field');
+    expect(kinds).toContain('range_action');
+  });
+});
+
+describe('resolveLanguage', () => {
+  it('maps aliases and ignores case and whitespace', () => {
+    const cases: Record<string, string> = {
+      js: 'javascript',
+      JSX: 'javascript',
+      mjs: 'javascript',
+      cjs: 'javascript',
+      node: 'javascript',
+      ts: 'typescript',
+      mts: 'typescript',
+      cts: 'typescript',
+      Py: 'python',
+      python3: 'python',
+      rs: 'rust',
+      Rust: 'rust',
+      golang: 'go',
+      ml: 'ocaml',
+      hs: 'haskell',
+      ex: 'elixir',
+      exs: 'elixir',
+      tmpl: 'gotmpl',
+      gohtml: 'gotmpl',
+      md: 'markdown',
+      protobuf: 'proto',
+      jsonc: 'json5',
+      ' JSON ': 'json',
+    };
+    for (const [input, language] of Object.entries(cases)) {
+      expect(resolveLanguage(input)).toBe(language);
+    }
+  });
+
+  it('rejects unknown names and non-strings', () => {
+    expect(resolveLanguage('klingon')).toBeNull();
+    expect(resolveLanguage('toString')).toBeNull();
+    expect(resolveLanguage(42 as unknown as string)).toBeNull();
+  });
+
+  it('resolves file extensions in detectLanguage', () => {
+    expect(detectLanguage('main.RS')).toBe('rust');
+    expect(detectLanguage('tsconfig.jsonc')).toBe('json5');
+    expect(detectLanguage('default.nix')).toBe('nix');
+    expect(detectLanguage('Makefile')).toBeNull();
+  });
+
+  it('is used by parse()', async () => {
+    const result = await parser.parse('let x = 1;', 'JS');
+    expect(result.success).toBe(true);
+    expect(result.ast?.kind).toBe('program');
+  });
+});
+
+describe('proto', () => {
+  it('parses a message with fields and an enum', async () => {
+    const code = [
+      'syntax = "proto3";',
+      '',
+      'message User {',
+      '  string name = 1;',
+      '  repeated string emails = 2;',
+      '  Role role = 3;',
+      '}',
+      '',
+      'enum Role {',
+      '  ROLE_UNSPECIFIED = 0;',
+      '  ROLE_ADMIN = 1;',
+      '}',
+      '',
+    ].join('\n');
+    const kinds = await cleanKinds(code, 'protobuf');
+    expect(kinds).toContain('message');
+    expect(kinds).toContain('field');
+    expect(kinds).toContain('enum');
+  });
+});
+
+describe('json5', () => {
+  it('accepts comments and trailing commas that strict JSON rejects', async () => {
+    const code = '{\n  // editor settings\n  "tabSize": 2,\n  "rulers": [80, 100,],\n}\n';
+    const strictError = await parser.withTree(code, 'json', (tree) => tree.rootNode.hasError);
+    expect(strictError).toBe(true);
+    await cleanKinds(code, 'jsonc');
+  });
+});
+
+describe('nix', () => {
+  it('parses an attribute set and a let ... in expression', async () => {
+    const code = 'let\n  name = "spectree";\n  port = 8080;\nin {\n  inherit name;\n  url = "http://localhost:${toString port}";\n}\n';
+    const kinds = await cleanKinds(code, 'nix');
+    expect(kinds).toContain('let_expression');
+    expect(kinds).toContain('attrset_expression');
+    expect(kinds).toContain('inherit');
+  });
+});
+
+describe('r', () => {
+  it('parses a function definition and a pipe', async () => {
+    const code = 'scale <- function(x) {\n  (x - mean(x)) / sd(x)\n}\n\nvalues |> scale() |> summary()\n';
+    const kinds = await cleanKinds(code, 'R');
+    expect(kinds).toContain('function_definition');
+    expect(kinds).toContain('|>');
+  });
+});
@@ -0,0 +1,77 @@
+// ============================================
+// Language registry
+// ============================================
+
+// Supported languages and their WASM file paths
+export const LANGUAGE_WASM: Record<string, string> = {
+  json: '/parsers/tree-sitter-json.wasm',
+  json5: '/parsers/tree-sitter-json5.wasm',
+  rust: '/parsers/tree-sitter-rust.wasm',
+  javascript: '/parsers/tree-sitter-javascript.wasm',
+  typescript: '/parsers/tree-sitter-typescript.wasm',
+  tsx: '/parsers/tree-sitter-tsx.wasm',
+  python: '/parsers/tree-sitter-python.wasm',
+  go: '/parsers/tree-sitter-go.wasm',
+  ocaml: '/parsers/tree-sitter-ocaml.wasm',
+  haskell: '/parsers/tree-sitter-haskell.wasm',
+  zig: '/parsers/tree-sitter-zig.wasm',
+  elixir: '/parsers/tree-sitter-elixir.wasm',
+  gotmpl: '/parsers/tree-sitter-gotmpl.wasm',
+  markdown: '/parsers/tree-sitter-markdown.wasm',
+  proto: '/parsers/tree-sitter-proto.wasm',
+  nix: '/parsers/tree-sitter-nix.wasm',
+  r: '/parsers/tree-sitter-r.wasm',
+};
+
+// Alternate names accepted for supported languages (matched lowercase)
+const LANGUAGE_ALIASES: Record<string, string> = {
+  js: 'javascript',
+  jsx: 'javascript',
+  mjs: 'javascript',
+  cjs: 'javascript',
+  node: 'javascript',
+  ts: 'typescript',
+  mts: 'typescript',
+  cts: 'typescript',
+  py: 'python',
+  python3: 'python',
+  rs: 'rust',
+  golang: 'go',
+  ml: 'ocaml',
+  hs: 'haskell',
+  ex: 'elixir',
+  exs: 'elixir',
+  tmpl: 'gotmpl',
+  gohtml: 'gotmpl',
+  md: 'markdown',
+  protobuf: 'proto',
+  jsonc: 'json5',
+};
+
+function hasOwn(table: Record<string, string>, key: string): boolean {
+  return Object.prototype.hasOwnProperty.call(table, key);
+}
+
+/**
+ * Map a user-supplied language name to a supported language, ignoring case
+ * and accepting common aliases (`js`, `ts`, `py`, `rs`, ...). Returns null
+ * for unsupported languages.
+ */
+export function resolveLanguage(input: string): string | null {
+  // Untyped callers (e.g. postMessage payloads) may pass anything
+  if (typeof input !== 'string') return null;
+  const name = input.trim().toLowerCase();
+  if (hasOwn(LANGUAGE_WASM, name)) return name;
+  if (hasOwn(LANGUAGE_ALIASES, name)) return LANGUAGE_ALIASES[name];
+  return null;
+}
+
+/**
+ * Guess a file's language from its name's extension. Returns null when the
+ * extension isn't recognized.
+ */
+export function detectLanguage(filename: string): string | null {
+  const dot = filename.lastIndexOf('.');
+  if (dot === -1) return null;
+  return resolveLanguage(filename.slice(dot + 1));
+}
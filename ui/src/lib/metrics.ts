@@ -0,0 +1,232 @@
+import type Parser from 'web-tree-sitter';
+import { parser, type AstNode } from './parser';
+import { walkAst } from './ast-utils';
+import { kindsFor } from './kinds';
+import { toNodeSpan, type NodeSpan } from './navigation';
+
+// ============================================
+// Types
+// ============================================
+
+export interface DistinctKinds {
+  /** Unique kinds, sorted */
+  kinds: string[];
+  count: number;
+}
+
+export interface FunctionComplexity extends NodeSpan {
+  /** Text of the function's `name` field, if it has one */
+  name?: string;
+  /** 1 + decision points: an approximation of cyclomatic complexity */
+  complexity: number;
+}
+
+export interface MaxNesting {
+  /** Depth of the deepest node (root = 0) */
+  depth: number;
+  /** Kinds from the root down to the deepest node */
+  kindPath: string[];
+  position: { row: number; column: number };
+}
+
+export interface MetricsInput {
+  path: string;
+  code: string;
+  language: string;
+}
+
+export interface AggregateMetrics {
+  files: number;
+  totalNodes: number;
+  totalFunctions: number;
+  /** ERROR and MISSING nodes across all files */
+  totalErrors: number;
+  /** Files per language, as given in the inputs */
+  filesByLanguage: Record<string, number>;
+  /** Most common kinds across all files, most frequent first */
+  topKinds: { kind: string; count: number }[];
+  /** Paths that couldn't be parsed (e.g. unsupported language) */
+  failed: string[];
+}
+
+// ============================================
+// Helpers
+// ============================================
+
+/** Decision points under `node`, not counting nested functions. */
+function countDecisions(
+  node: Parser.SyntaxNode,
+  decisions: Set<string>,
+  functions: Set<string>,
+): number {
+  let count = 0;
+  for (let i = 0; i < node.childCount; i++) {
+    const child = node.child(i)!;
+    if (functions.has(child.type)) continue;
+    if (decisions.has(child.type)) count++;
+    count += countDecisions(child, decisions, functions);
+  }
+  return count;
+}
+
+interface TreeTally {
+  nodes: number;
+  functions: number;
+  errors: number;
+}
+
+/** Count nodes, functions and errors in one walk, adding kinds to `kinds`. */
+function tallyTree(
+  tree: Parser.Tree,
+  functions: Set<string>,
+  kinds: Map<string, number>,
+): TreeTally {
+  const tally: TreeTally = { nodes: 0, functions: 0, errors: 0 };
+  const cursor = tree.walk();
+  try {
+    for (;;) {
+      const kind = cursor.nodeType;
+      tally.nodes++;
+      if (functions.has(kind)) tally.functions++;
+      if (kind === 'ERROR' || cursor.nodeIsMissing) tally.errors++;
+      kinds.set(kind, (kinds.get(kind) ?? 0) + 1);
+
+      if (cursor.gotoFirstChild() || cursor.gotoNextSibling()) continue;
+      let climbing = true;
+      while (climbing) {
+        if (!cursor.gotoParent()) return tally;
+        climbing = !cursor.gotoNextSibling();
+      }
+    }
+  } finally {
+    cursor.delete();
+  }
+}
+
+// ============================================
+// Public API
+// ============================================
+
+/**
+ * Unique node kinds present in a tree: a quick measure of how varied a
+ * file's syntax is. With `namedOnly`, punctuation and keywords are ignored.
+ */
+export function distinctKinds(ast: AstNode, namedOnly = false): DistinctKinds {
+  const seen = new Set<string>();
+  walkAst(ast, (node) => {
+    if (!namedOnly || node.isNamed) seen.add(node.kind);
+  });
+  const kinds = [...seen].sort();
+  return { kinds, count: kinds.length };
+}
+
+/**
+ * The deepest node in a tree and how it's reached, a cheap signal for
+ * pathologically nested code (long call chains, nested ternaries). The
+ * first node found at the maximum depth wins.
+ */
+export function maxNesting(ast: AstNode): MaxNesting {
+  let deepest = { node: ast, depth: 0, path: [ast.kind] };
+  const path: string[] = [];
+
+  const visit = (node: AstNode, depth: number) => {
+    path.push(node.kind);
+    if (depth > deepest.depth) deepest = { node, depth, path: [...path] };
+    for (const child of node.children) visit(child, depth + 1);
+    path.pop();
+  };
+  visit(ast, 0);
+
+  return {
+    depth: deepest.depth,
+    kindPath: deepest.path,
+    position: deepest.node.startPosition,
+  };
+}
+
+/**
+ * Approximate cyclomatic complexity of every function, method and closure:
+ * one plus the number of decision points (branches, loops, case arms,
+ * short-circuit operators, catches) in its body. Nested functions are
+ * scored separately rather than added to their parent.
+ */
+export async function complexity(code: string, language: string): Promise<FunctionComplexity[]> {
+  const kinds = kindsFor(language);
+  const functions = new Set(kinds.functions);
+  const decisions = new Set(kinds.decisions);
+  if (functions.size === 0) return [];
+
+  return parser.withTree(code, language, (tree) =>
+    tree.rootNode.descendantsOfType(kinds.functions).map((fn) => ({
+      ...toNodeSpan(fn),
+      name: fn.childForFieldName('name')?.text,
+      complexity: 1 + countDecisions(fn, decisions, functions),
+    })),
+  );
+}
+
+/**
+ * How often each identifier appears, by text, counting every identifier
+ * kind in the language's table (variables, fields, types, ...). A quick
+ * view of the most-referenced names.
+ */
+export async function identifierFrequency(
+  code: string,
+  language: string,
+): Promise<Record<string, number>> {
+  const { identifiers } = kindsFor(language);
+  if (identifiers.length === 0) return {};
+
+  return parser.withTree(code, language, (tree) => {
+    const counts = new Map<string, number>();
+    for (const node of tree.rootNode.descendantsOfType(identifiers)) {
+      counts.set(node.text, (counts.get(node.text) ?? 0) + 1);
+    }
+    return Object.fromEntries(counts);
+  });
+}
+
+/**
+ * Totals across many files for dashboards: node, function and error
+ * counts, files per language and the `topN` most common kinds. Each tree
+ * is walked once and freed before the next file is parsed, and no
+ * per-file detail is kept. Files that fail to parse are listed in
+ * `failed` rather than aborting the batch.
+ */
+export async function aggregateMetrics(
+  inputs: MetricsInput[],
+  topN = 10,
+): Promise<AggregateMetrics> {
+  const totals: AggregateMetrics = {
+    files: 0,
+    totalNodes: 0,
+    totalFunctions: 0,
+    totalErrors: 0,
+    filesByLanguage: {},
+    topKinds: [],
+    failed: [],
+  };
+  const kinds = new Map<string, number>();
+
+  for (const { path, code, language } of inputs) {
+    const functions = new Set(kindsFor(language).functions);
+    let tally: TreeTally;
+    try {
+      tally = await parser.withTree(code, language, (tree) => tallyTree(tree, functions, kinds));
+    } catch {
+      totals.failed.push(path);
+      continue;
+    }
+    totals.files++;
+    totals.totalNodes += tally.nodes;
+    totals.totalFunctions += tally.functions;
+    totals.totalErrors += tally.errors;
+    totals.filesByLanguage[language] = (totals.filesByLanguage[language] ?? 0) + 1;
+  }
+
+  totals.topKinds = [...kinds]
+    .sort((a, b) => b[1] - a[1] || a[0].localeCompare(b[0]))
+    .slice(0, topN)
+    .map(([kind, count]) => ({ kind, count }));
+  return totals;
+}
@@ -0,0 +1,108 @@
+import { describe, expect, it } from 'vitest';
+import {
+  childrenAtPath,
+  commonAncestor,
+  contextAtPosition,
+  isAncestorRange,
+  kindPathAtPosition,
+  nearestAncestorOfKinds,
+  nodesAtExactRange,
+  nodesInLineRanges,
+  textAtPath,
+} from './navigation';
+
+const json = '{"a": [1, 2]}';
+
+describe('textAtPath', () => {
+  it('returns the text of the node at a path', async () => {
+    expect(await textAtPath(json, 'json', '0.1.2')).toBe('[1, 2]');
+    expect(await textAtPath(json, 'json', '0.1.2.3')).toBe('2');
+  });
+
+  it('returns the whole source for the empty path', async () => {
+    expect(await textAtPath(json, 'json', '')).toBe(json);
+  });
+
+  it('throws for an out-of-range index or a malformed path', async () => {
+    await expect(textAtPath(json, 'json', '0.7')).rejects.toThrow('No node at path: 0.7');
+    await expect(textAtPath(json, 'json', '0.-1')).rejects.toThrow('Invalid node path: 0.-1');
+  });
+});
+
+describe('childrenAtPath', () => {
+  it('lists one level of children with fields and child counts', async () => {
+    const children = await childrenAtPath(json, 'json', '0.1');
+    expect(children.map(({ kind, isNamed, fieldName }) => [kind, isNamed, fieldName])).toEqual([
+      ['string', true, 'key'],
+      [':', false, undefined],
+      ['array', true, 'value'],
+    ]);
+    expect(children[2]).toMatchObject({ start: 6, end: 12, childCount: 5 });
+  });
+
+  it('lists the root children for the empty path', async () => {
+    const children = await childrenAtPath(json, 'json', '');
+    expect(children.map((child) => child.kind)).toEqual(['object']);
+  });
+
+  it('throws for an out-of-range index', async () => {
+    await expect(childrenAtPath(json, 'json', '0.1.9')).rejects.toThrow('No node at path: 0.1.9');
+  });
+});
+
+describe('ancestors', () => {
+  const code = 'function outer() {\n  const s = "hi"; // note\n  return s;\n}\n';
+
+  it('finds the common ancestor of two offsets', async () => {
+    const span = await commonAncestor(code, 'javascript', code.indexOf('const'), code.indexOf('return'));
+    expect(span.kind).toBe('statement_block');
+  });
+
+  it('finds the nearest enclosing node of a kind, with its name', async () => {
+    const match = await nearestAncestorOfKinds(code, 'javascript', code.indexOf('return'), ['function_declaration']);
+    expect(match).toMatchObject({ kind: 'function_declaration', name: 'outer', start: 0 });
+    expect(await nearestAncestorOfKinds(code, 'javascript', 0, ['class_declaration'])).toBeNull();
+  });
+
+  it('checks whether one range lies inside another', async () => {
+    const body = [code.indexOf('{'), code.lastIndexOf('}') + 1] as const;
+    const ret = [code.indexOf('return'), code.indexOf('s;\n}') + 2] as const;
+    expect(await isAncestorRange(code, 'javascript', ...body, ...ret)).toBe(true);
+    expect(await isAncestorRange(code, 'javascript', ...ret, ...body)).toBe(false);
+    expect(await isAncestorRange(code, 'javascript', ...body, ...body)).toBe(false);
+  });
+
+  it('reports string and comment context', async () => {
+    expect(await contextAtPosition(code, 'javascript', code.indexOf('hi'))).toMatchObject({ inString: true, inComment: false });
+    expect(await contextAtPosition(code, 'javascript', code.indexOf('note'))).toMatchObject({ inString: false, inComment: true });
+  });
+
+  it('prints the kind path to a position', async () => {
+    expect(await kindPathAtPosition(code, 'javascript', 2, 2)).toBe(
+      'program > function_declaration > statement_block > return_statement > return',
+    );
+  });
+});
+
+describe('nodesInLineRanges', () => {
+  it('returns only named nodes on the requested lines', async () => {
+    const code = 'let a = 1;\nlet b = 2;\nlet c = 3;\n';
+    const spans = await nodesInLineRanges(code, 'javascript', [[1, 1]]);
+    expect(spans[0].kind).toBe('program');
+    expect(spans.slice(1).every((span) => span.startPosition.row === 1)).toBe(true);
+    expect(spans.some((span) => span.kind === 'lexical_declaration')).toBe(true);
+  });
+});
+
+describe('nodesAtExactRange', () => {
+  it('lists the wrappers sharing a range, outermost first', async () => {
+    const code = 'f(x);';
+    const spans = await nodesAtExactRange(code, 'javascript', 0, 4);
+    expect(spans.map((span) => span.kind)).toEqual(['call_expression']);
+    expect((await nodesAtExactRange(code, 'javascript', 0, 5)).map((span) => span.kind)).toEqual([
+      'program',
+      'expression_statement',
+    ]);
+    expect(await nodesAtExactRange(code, 'javascript', 1, 3)).toEqual([]);
+  });
+});
@@ -0,0 +1,299 @@
+import type Parser from 'web-tree-sitter';
+import { parser } from './parser';
+import { kindsFor } from './kinds';
+
+// ============================================
+// Types
+// ============================================
+
+/** A node's kind and source range, without its subtree */
+export interface NodeSpan {
+  kind: string;
+  start: number;
+  end: number;
+  startPosition: { row: number; column: number };
+  endPosition: { row: number; column: number };
+}
+
+export interface AncestorMatch extends NodeSpan {
+  /** Text of the node's `name` field, if it has one */
+  name?: string;
+}
+
+export interface PositionContext {
+  inString: boolean;
+  inComment: boolean;
+  /** Kind of the innermost node at the position */
+  nodeKind: string;
+}
+
+export interface ChildSummary extends NodeSpan {
+  isNamed: boolean;
+  /** Number of this child's own children, to know if it can be expanded */
+  childCount: number;
+  /** Field the child occupies in the parent, if any */
+  fieldName?: string;
+}
+
+export function toNodeSpan(node: Parser.SyntaxNode): NodeSpan {
+  return {
+    kind: node.type,
+    start: node.startIndex,
+    end: node.endIndex,
+    startPosition: node.startPosition,
+    endPosition: node.endPosition,
+  };
+}
+
+// ============================================
+// Tree helpers
+// ============================================
+
+/** Parse a child-index path like `"0.3.1"`; throws if malformed. */
+function pathSteps(path: string): number[] {
+  const steps = path === '' ? [] : path.split('.').map(Number);
+  if (steps.some((step) => !Number.isInteger(step) || step < 0)) {
+    throw new Error(`Invalid node path: ${path}`);
+  }
+  return steps;
+}
+
+function nodeAtPath(root: Parser.SyntaxNode, steps: number[], path: string): Parser.SyntaxNode {
+  let node = root;
+  for (const step of steps) {
+    const child = node.child(step);
+    if (!child) throw new Error(`No node at path: ${path}`);
+    node = child;
+  }
+  return node;
+}
+
+/** Ancestors of a node, from its parent up to the root. */
+function ancestors(node: Parser.SyntaxNode): Parser.SyntaxNode[] {
+  const chain: Parser.SyntaxNode[] = [];
+  for (let current = node.parent; current; current = current.parent) {
+    chain.push(current);
+  }
+  return chain;
+}
+
+// ============================================
+// Public API
+// ============================================
+
+/**
+ * Find the lowest common ancestor of the nodes at two offsets, e.g. the
+ * block containing both the cursor and the selection anchor.
+ */
+export async function commonAncestor(
+  code: string,
+  language: string,
+  offsetA: number,
+  offsetB: number,
+): Promise<NodeSpan> {
+  return parser.withTree(code, language, (tree) => {
+    const a = tree.rootNode.descendantForIndex(offsetA);
+    const b = tree.rootNode.descendantForIndex(offsetB);
+
+    const chainA = new Set([a, ...ancestors(a)].map((n) => n.id));
+    for (const node of [b, ...ancestors(b)]) {
+      if (chainA.has(node.id)) return toNodeSpan(node);
+    }
+    return toNodeSpan(tree.rootNode);
+  });
+}
+
+/**
+ * Walk up from the node at `offset` to the nearest node whose kind is in
+ * `kinds` (e.g. "go to enclosing declaration"). Returns null if none match.
+ */
+export async function nearestAncestorOfKinds(
+  code: string,
+  language: string,
+  offset: number,
+  kinds: string[],
+): Promise<AncestorMatch | null> {
+  const wanted = new Set(kinds);
+  return parser.withTree(code, language, (tree) => {
+    const start = tree.rootNode.descendantForIndex(offset);
+    for (const node of [start, ...ancestors(start)]) {
+      if (wanted.has(node.type)) {
+        return {
+          ...toNodeSpan(node),
+          name: node.childForFieldName('name')?.text,
+        };
+      }
+    }
+    return null;
+  });
+}
+
+/**
+ * Report whether an offset falls inside a string or comment, so editor
+ * actions like autocomplete can be suppressed there.
+ */
+export async function contextAtPosition(
+  code: string,
+  language: string,
+  offset: number,
+): Promise<PositionContext> {
+  const { strings, comments } = kindsFor(language);
+  return parser.withTree(code, language, (tree) => {
+    const node = tree.rootNode.descendantForIndex(offset);
+    const chain = [node, ...ancestors(node)].map((n) => n.type);
+    return {
+      inString: chain.some((kind) => strings.includes(kind)),
+      inComment: chain.some((kind) => comments.includes(kind)),
+      nodeKind: node.type,
+    };
+  });
+}
+
+/**
+ * Human-readable kind path from the root to the node at a point, e.g.
+ * `source_file > function_item > block > call_expression`. Handy when
+ * debugging how a grammar structures some code.
+ */
+export async function kindPathAtPosition(
+  code: string,
+  language: string,
+  row: number,
+  column: number,
+): Promise<string> {
+  return parser.withTree(code, language, (tree) => {
+    const node = tree.rootNode.descendantForPosition({ row, column });
+    return [node, ...ancestors(node)]
+      .reverse()
+      .map((n) => n.type)
+      .join(' > ');
+  });
+}
+
+/**
+ * All named nodes overlapping any of the inclusive, 0-indexed
+ * `[startLine, endLine]` ranges, in document order; e.g. the visible parts
+ * of a split editor viewport in one call. Subtrees outside every range are
+ * skipped without being visited.
+ */
+export async function nodesInLineRanges(
+  code: string,
+  language: string,
+  ranges: [number, number][],
+): Promise<NodeSpan[]> {
+  const overlaps = (startRow: number, endRow: number) =>
+    ranges.some(([startLine, endLine]) => startRow <= endLine && endRow >= startLine);
+
+  return parser.withTree(code, language, (tree) => {
+    const found: NodeSpan[] = [];
+    const cursor = tree.walk();
+    try {
+      for (;;) {
+        const inRange = overlaps(cursor.startPosition.row, cursor.endPosition.row);
+        if (inRange && cursor.nodeIsNamed) found.push(toNodeSpan(cursor.currentNode));
+
+        // Descendants lie within their ancestor, so a miss prunes the subtree
+        if ((inRange && cursor.gotoFirstChild()) || cursor.gotoNextSibling()) continue;
+        let climbing = true;
+        while (climbing) {
+          if (!cursor.gotoParent()) return found;
+          climbing = !cursor.gotoNextSibling();
+        }
+      }
+    } finally {
+      cursor.delete();
+    }
+  });
+}
+
+/**
+ * Source text of the node at a child-index path such as `"0.3.1"` (child 0
+ * of the root, then its child 3, then that node's child 1), matching the
+ * `children` arrays of the default AST. An empty path is the root. Lets a
+ * frontend holding only the tree's structure fetch text lazily. Throws if
+ * the path doesn't resolve.
+ */
+export async function textAtPath(code: string, language: string, path: string): Promise<string> {
+  const steps = pathSteps(path);
+  return parser.withTree(code, language, (tree) => nodeAtPath(tree.rootNode, steps, path).text);
+}
+
+/**
+ * The direct children of the node at `path` (see `textAtPath()`), one level
+ * only, each with its own `childCount`: what a lazy tree viewer fetches
+ * when a node is expanded, without serializing the rest of the subtree.
+ * Throws if the path doesn't resolve.
+ */
+export async function childrenAtPath(
+  code: string,
+  language: string,
+  path: string,
+): Promise<ChildSummary[]> {
+  const steps = pathSteps(path);
+  return parser.withTree(code, language, (tree) => {
+    const node = nodeAtPath(tree.rootNode, steps, path);
+    const children: ChildSummary[] = [];
+    for (let i = 0; i < node.childCount; i++) {
+      const child = node.child(i)!;
+      const summary: ChildSummary = {
+        ...toNodeSpan(child),
+        isNamed: child.isNamed,
+        childCount: child.childCount,
+      };
+      const field = node.fieldNameForChild(i);
+      if (field) summary.fieldName = field;
+      children.push(summary);
+    }
+    return children;
+  });
+}
+
+/**
+ * Every node whose range is exactly `[start, end)`, outermost to innermost:
+ * the chain of single-child wrappers (`expression_statement` >
+ * `call_expression`, ...) that otherwise hide behind the innermost node.
+ * Empty when no node spans exactly that range.
+ */
+export async function nodesAtExactRange(
+  code: string,
+  language: string,
+  start: number,
+  end: number,
+): Promise<NodeSpan[]> {
+  return parser.withTree(code, language, (tree) => {
+    const found: NodeSpan[] = [];
+    let node: Parser.SyntaxNode | null = tree.rootNode;
+    while (node && node.startIndex <= start && node.endIndex >= end) {
+      if (node.startIndex === start && node.endIndex === end) found.push(toNodeSpan(node));
+      let next: Parser.SyntaxNode | null = null;
+      for (const child of node.children) {
+        if (child.startIndex <= start && child.endIndex >= end) {
+          next = child;
+          break;
+        }
+      }
+      node = next;
+    }
+    return found;
+  });
+}
+
+/**
+ * Whether the smallest node covering `[innerStart, innerEnd)` lies strictly
+ * inside the smallest node covering `[outerStart, outerEnd)`, e.g. to check
+ * whether a structural editor may drop one selection into another. False
+ * when both ranges resolve to the same node.
+ */
+export async function isAncestorRange(
+  code: string,
+  language: string,
+  outerStart: number,
+  outerEnd: number,
+  innerStart: number,
+  innerEnd: number,
+): Promise<boolean> {
+  return parser.withTree(code, language, (tree) => {
+    const outer = tree.rootNode.descendantForIndex(outerStart, outerEnd);
+    const inner = tree.rootNode.descendantForIndex(innerStart, innerEnd);
+    return ancestors(inner).some((node) => node.id === outer.id);
+  });
+}
@@ -0,0 +1,387 @@
+import { describe, expect, it } from 'vitest';
+import { parser, type AstNode } from './parser';
+import { firstNodeByKind, reconstructSource, walkAst } from './ast-utils';
+
+describe('parseExpression', () => {
+  it('parses a Rust expression with offsets relative to the fragment', async () => {
+    const result = await parser.parseExpression('a + b * 2', 'rust');
+    expect(result.success).toBe(true);
+    expect(result.ast).toMatchObject({
+      kind: 'binary_expression',
+      start: 0,
+      end: 9,
+      startPosition: { row: 0, column: 0 },
+      endPosition: { row: 0, column: 9 },
+    });
+  });
+
+  it('parses a Python expression with offsets relative to the fragment', async () => {
+    const result = await parser.parseExpression('  foo(x, 1)', 'py');
+    expect(result.success).toBe(true);
+    expect(result.ast).toMatchObject({ kind: 'call', start: 2, end: 11 });
+  });
+
+  it('rejects fragments that are not a single expression', async () => {
+    for (const code of ['a; b', 'foo }']) {
+      const result = await parser.parseExpression(code, 'rust');
+      expect(result).toMatchObject({ success: false, error: 'Not a single expression' });
+      expect(result.ast).toBeUndefined();
+    }
+  });
+
+  it('rejects several expressions in languages without a scaffold', async () => {
+    for (const [code, language] of [['1\n2', 'r'], ['{} {}', 'json']]) {
+      const result = await parser.parseExpression(code, language);
+      expect(result).toMatchObject({ success: false, error: 'Not a single expression' });
+      expect(result.ast).toBeUndefined();
+    }
+  });
+
+  it('accepts a single expression in a language without a scaffold', async () => {
+    const result = await parser.parseExpression(' {"a": 1} ', 'json');
+    expect(result.success).toBe(true);
+    expect(result.ast).toMatchObject({ kind: 'object', start: 1, end: 9 });
+  });
+
+  it('reports syntax errors in the expression', async () => {
+    const result = await parser.parseExpression('(1 +', 'python');
+    expect(result.success).toBe(false);
+  });
+});
+
+describe('parseDiffUpdate', () => {
+  it('reports the ranges changed by an edit', async () => {
+    const result = await parser.parseDiffUpdate('[1, 2]', '[1, {}]', 'json');
+    expect(result.success).toBe(true);
+    expect(result.changedRanges.length).toBeGreaterThan(0);
+  });
+
+  it('applies the input length limit to the new code', async () => {
+    parser.setMaxInputLength(8);
+    try {
+      const result = await parser.parseDiffUpdate('[1]', '[1, 2, 3, 4]', 'json');
+      expect(result).toMatchObject({ success: false, errorKind: 'InputTooLarge' });
+    } finally {
+      parser.setMaxInputLength(50 * 1024 * 1024);
+    }
+  });
+});
+
+describe('shebang handling', () => {
+  it('parses a Python script with a shebang without errors', async () => {
+    const code = '#!/usr/bin/env python\nimport sys\nprint(sys.argv)\n';
+    const result = await parser.parse(code, 'python');
+    expect(result.success).toBe(true);
+
+    const kinds = result.ast!.children.map((child) => child.kind);
+    expect(kinds).toEqual(['comment', 'import_statement', 'expression_statement']);
+    expect(result.ast!.children[1].start).toBe(code.indexOf('import'));
+    expect(JSON.stringify(result.ast)).not.toContain('"ERROR"');
+  });
+});
+
+describe('plain text', () => {
+  it('accepts the pseudo-language names in any case', async () => {
+    for (const language of ['text', 'Text', 'PlainText', ' TXT ']) {
+      const result = await parser.parse('hello\nworld', language);
+      expect(result.success).toBe(true);
+      expect(result.ast).toMatchObject({ kind: 'text', start: 0, end: 11, text: 'hello\nworld' });
+    }
+  });
+});
+
+describe('excludeRoot', () => {
+  it('numbers the new root 0 with includeIds', async () => {
+    const { ast } = await parser.parse('[1, 2]', 'json', { excludeRoot: true, includeIds: true });
+    expect(ast).toMatchObject({ kind: 'array', id: 0 });
+    expect(ast!.children.map((child) => child.id)).toEqual([1, 2, 3, 4, 5]);
+  });
+});
+
+describe('sortChildren', () => {
+  it('keeps children non-decreasing in start around error recovery', async () => {
+    const code = 'fn main() {\n  let x = ; /* note */\n  foo(1,\n}\n}';
+    const { ast } = await parser.parse(code, 'rust', { sortChildren: true });
+    walkAst(ast!, (node) => {
+      for (let i = 1; i < node.children.length; i++) {
+        expect(node.children[i].start).toBeGreaterThanOrEqual(node.children[i - 1].start);
+      }
+    });
+  });
+});
+
+describe('includeGrammarNames', () => {
+  it('reports the rule behind aliased kinds only', async () => {
+    const { ast } = await parser.parse('struct Point { x: i32 }', 'rust', { includeGrammarNames: true });
+    // tree-sitter-rust aliases `identifier` as `type_identifier` and `field_identifier`
+    expect(firstNodeByKind(ast!, 'type_identifier')?.grammarName).toBe('identifier');
+    expect(firstNodeByKind(ast!, 'field_identifier')?.grammarName).toBe('identifier');
+    expect(firstNodeByKind(ast!, 'primitive_type')?.grammarName).toBeUndefined();
+  });
+});
+
+describe('minLength', () => {
+  it('drops single-character identifiers at 2 and keeps longer ones', async () => {
+    const { ast } = await parser.parse('let a = 1; let total = a + 10;', 'javascript', { minLength: 2 });
+    const identifiers: string[] = [];
+    walkAst(ast!, (node) => {
+      if (node.kind === 'identifier') identifiers.push(node.text!);
+    });
+    expect(identifiers).toEqual(['total']);
+  });
+
+  it('keeps everything at 0', async () => {
+    const { ast } = await parser.parse('let a = 1;', 'javascript', { minLength: 0 });
+    expect(firstNodeByKind(ast!, 'identifier')?.text).toBe('a');
+  });
+});
+
+describe('parseBytes', () => {
+  it('decodes legacy encodings with offsets into the decoded text', async () => {
+    // "café" in windows-1252: é is the single byte 0xe9
+    const bytes = new Uint8Array([0x5b, 0x22, 0x63, 0x61, 0x66, 0xe9, 0x22, 0x5d]);
+    const result = await parser.parseBytes(bytes, 'json', 'windows-1252');
+    expect(result.success).toBe(true);
+    const string = firstNodeByKind(result.ast!, 'string')!;
+    expect(string).toMatchObject({ start: 1, end: 7 });
+  });
+
+  it('leaves text off leaves covering invalid bytes', async () => {
+    const bytes = new Uint8Array([0x5b, 0x22, 0xff, 0x22, 0x2c, 0x31, 0x5d]);
+    const result = await parser.parseBytes(bytes, 'json', 'utf-8', { includeTextSafety: true });
+    expect(result.success).toBe(true);
+
+    let withoutText = 0;
+    walkAst(result.ast!, (node) => {
+      expect(node.text ?? '').not.toContain('\uFFFD');
+      if (node.children.length === 0 && node.text === undefined) {
+        withoutText++;
+        expect(node.textSafe).toBe(false);
+      }
+    });
+    expect(withoutText).toBeGreaterThan(0);
+    expect(firstNodeByKind(result.ast!, 'number')?.text).toBe('1');
+  });
+
+  it('rejects unknown encodings', async () => {
+    const result = await parser.parseBytes(new Uint8Array(), 'json', 'no-such-encoding');
+    expect(result).toMatchObject({ success: false, error: 'Unsupported encoding: no-such-encoding' });
+  });
+
+  it('passes lone surrogates through as one UTF-16 unit', async () => {
+    const code = '"a\uD800b"';
+    const { ast } = await parser.parse(code, 'javascript', { includeTextSafety: true });
+    const string = firstNodeByKind(ast!, 'string')!;
+    expect(string.end).toBe(code.length);
+  });
+});
+
+describe('textPreviewLength', () => {
+  it('collapses a multi-line string literal to one line', async () => {
+    const code = 'package p\n\nvar s = `first line\n\t\tsecond line`\n';
+    const { ast } = await parser.parse(code, 'go', { textPreviewLength: 15 });
+
+    const leaves: AstNode[] = [];
+    walkAst(ast!, (node) => {
+      if (node.children.length === 0) leaves.push(node);
+    });
+    const { text, start, end } = leaves.find((leaf) => leaf.text?.startsWith('`first'))!;
+    expect(text).toBe('`first line sec…');
+    expect(code.slice(start, end)).toBe('`first line\n\t\tsecond line`');
+  });
+
+  it('never splits a surrogate pair', async () => {
+    const { ast } = await parser.parse('const s = "a😀b";', 'javascript', { textPreviewLength: 2 });
+    expect(firstNodeByKind(ast!, 'string_fragment')?.text).toBe('a…');
+  });
+});
+
+describe('parse on untrusted input', () => {
+  // Small deterministic PRNG so failures reproduce
+  function random(seed: number): () => number {
+    return () => {
+      seed = (seed * 1664525 + 1013904223) >>> 0;
+      return seed / 2 ** 32;
+    };
+  }
+
+  it('reports bad arguments as failures', async () => {
+    const cases: [unknown, unknown][] = [
+      [42, 'rust'],
+      [null, 'json'],
+      [undefined, 'python'],
+      ['x', 'klingon'],
+      ['x', undefined],
+      ['x', 7],
+      ['x', '__proto__'],
+    ];
+    for (const [code, language] of cases) {
+      const result = await parser.parse(code as string, language as string);
+      expect(result.success).toBe(false);
+      expect(typeof result.error).toBe('string');
+    }
+  });
+
+  it('never throws on random input', async () => {
+    const next = random(0x5eed);
+    const alphabet = '{}[]()<>"\'`\\/#;:,.=+-*&|!?\n\t \r\u0000𐀀é€😀abcXYZ019';
+    const languages = [...parser.getSupportedLanguages(), 'JS', 'text', '', 'nope'];
+
+    for (let i = 0; i < 200; i++) {
+      let code = '';
+      const length = Math.floor(next() * 200);
+      for (let j = 0; j < length; j++) {
+        code += alphabet[Math.floor(next() * alphabet.length)];
+      }
+      const language = languages[Math.floor(next() * languages.length)];
+      const result = await parser.parse(code, language);
+      expect(typeof result.success).toBe('boolean');
+      expect(result.language).toBe(language);
+    }
+  });
+
+  it('fails cleanly on deeply nested input', async () => {
+    const result = await parser.parse('['.repeat(100_000), 'json');
+    expect(typeof result.success).toBe('boolean');
+  });
+});
+
+describe('maxTextLength', () => {
+  it('cuts a 1MB string literal and keeps its full range', async () => {
+    const body = 'a'.repeat(1024 * 1024);
+    const code = `const s = "${body}";`;
+    const { ast } = await parser.parse(code, 'javascript', { maxTextLength: 100 });
+
+    const fragment = firstNodeByKind(ast!, 'string_fragment')!;
+    expect(fragment.text).toHaveLength(100);
+    expect(fragment.textTruncated).toBe(true);
+    expect(fragment.end - fragment.start).toBe(body.length);
+    expect(JSON.stringify(ast).length).toBeLessThan(10_000);
+  });
+
+  it('never splits a surrogate pair', async () => {
+    const { ast } = await parser.parse('const s = "a😀b";', 'javascript', { maxTextLength: 2 });
+    expect(firstNodeByKind(ast!, 'string_fragment')?.text).toBe('a');
+  });
+});
+
+describe('parseDual', () => {
+  it('returns both trees from the same source', async () => {
+    const code = 'def add(a, b):\n    return a + b\n';
+    const { success, named, full } = await parser.parseDual(code, 'python');
+    expect(success).toBe(true);
+    expect(named).toMatchObject({ kind: full!.kind, start: full!.start, end: full!.end });
+    expect(reconstructSource(full!, code).text).toBe(code.slice(full!.start, full!.end));
+
+    walkAst(named!, (node) => {
+      expect(node.isNamed).toBe(true);
+    });
+    const fullNamed: string[] = [];
+    walkAst(full!, (node) => {
+      if (node.isNamed) fullNamed.push(`${node.kind}@${node.start}`);
+    });
+    const namedOnly: string[] = [];
+    walkAst(named!, (node) => {
+      namedOnly.push(`${node.kind}@${node.start}`);
+    });
+    expect(namedOnly).toEqual(fullNamed);
+  });
+});
+
+describe('normalizeLineEndings', () => {
+  it('rewrites mixed line endings and reports it', async () => {
+    const code = 'a = 1\r\nb = 2\rc = 3\n';
+    const result = await parser.parse(code, 'python', { normalizeLineEndings: true, namedOnly: true });
+    expect(result.lineEndingsNormalized).toBe(true);
+
+    const statements = result.ast!.children;
+    expect(statements.map((s) => s.startPosition.row)).toEqual([0, 1, 2]);
+    // Offsets refer to the normalized text
+    expect(statements.map((s) => s.start)).toEqual([0, 6, 12]);
+  });
+
+  it('leaves the flag off when there was nothing to rewrite', async () => {
+    const result = await parser.parse('a = 1\n', 'python', { normalizeLineEndings: true });
+    expect(result.lineEndingsNormalized).toBeUndefined();
+  });
+});
+
+describe('trimTrailingNewline', () => {
+  it('gives files differing only by a final newline the same tree', async () => {
+    const withNewline = await parser.parse('x = 1\n', 'python', { trimTrailingNewline: true });
+    const without = await parser.parse('x = 1', 'python', { trimTrailingNewline: true });
+    expect(withNewline.trailingNewlineTrimmed).toBe(true);
+    expect(without.trailingNewlineTrimmed).toBeUndefined();
+    expect(withNewline.ast).toEqual(without.ast);
+  });
+
+  it('trims a single CRLF only', async () => {
+    const result = await parser.parse('x = 1\r\n\r\n', 'python', { trimTrailingNewline: true });
+    expect(result.trailingNewlineTrimmed).toBe(true);
+    expect(result.ast!.end).toBeLessThanOrEqual('x = 1\r\n'.length);
+  });
+});
+
+describe('includeText', () => {
+  it('leaves text off every node when false', async () => {
+    const { ast } = await parser.parse('let s = "hi" + x;', 'javascript', { includeText: false });
+    walkAst(ast!, (node) => {
+      expect(node.text).toBeUndefined();
+    });
+  });
+
+  it('keeps leaf text by default', async () => {
+    const { ast } = await parser.parse('let x;', 'javascript');
+    expect(firstNodeByKind(ast!, 'identifier')?.text).toBe('x');
+  });
+});
+
+describe('zeroWidth', () => {
+  it('flags the MISSING node error recovery inserts', async () => {
+    const { ast } = await parser.parse('foo(1', 'javascript');
+    const zeroWidth: AstNode[] = [];
+    walkAst(ast!, (node) => {
+      if (node.zeroWidth) zeroWidth.push(node);
+    });
+    expect(zeroWidth.map((node) => node.kind)).toContain(')');
+    for (const node of zeroWidth) expect(node.start).toBe(node.end);
+  });
+
+  it('is unset on nodes with a width', async () => {
+    const { ast } = await parser.parse('foo(1)', 'javascript');
+    walkAst(ast!, (node) => {
+      expect(node.zeroWidth).toBeUndefined();
+    });
+  });
+});
+
+describe('lineTextLength', () => {
+  it('attaches the line each node starts on', async () => {
+    const code = 'x = 1\ny = compute(x, 2)\n';
+    const { ast } = await parser.parse(code, 'python', { lineTextLength: 80 });
+    expect(firstNodeByKind(ast!, 'call')?.lineText).toBe('y = compute(x, 2)');
+    expect(firstNodeByKind(ast!, 'integer')?.lineText).toBe('x = 1');
+  });
+
+  it('cuts long lines', async () => {
+    const { ast } = await parser.parse('value = 1\n', 'python', { lineTextLength: 5 });
+    expect(firstNodeByKind(ast!, 'integer')?.lineText).toBe('value');
+  });
+});
+
+describe('kindAliases', () => {
+  it('renames mapped kinds and passes others through', async () => {
+    const { ast } = await parser.parse('let s = "hi";', 'javascript', {
+      kindAliases: { identifier: 'name', string: 'string_literal' },
+    });
+    const kinds = new Set<string>();
+    walkAst(ast!, (node) => {
+      kinds.add(node.kind);
+    });
+    expect(kinds).toContain('name');
+    expect(kinds).toContain('string_literal');
+    expect(kinds).not.toContain('identifier');
+    expect(kinds).not.toContain('string');
+    expect(kinds).toContain('lexical_declaration');
+  });
+});
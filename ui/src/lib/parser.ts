@@ -1,5 +1,15 @@
 import Parser from 'web-tree-sitter';
+import { firstNodeByKind, walkAst } from './ast-utils';
+import { computeEdit, pointAt, toChangedRange, type ChangedRange } from './edits';
+import { LANGUAGE_WASM, resolveLanguage } from './languages';
 
+// AstNode and ParseResult are mirrored by the JSON Schema in schema.ts, which
+// fails to compile when a field here is missing from it.
+//
+// Offsets and columns are UTF-16 code unit indices into the source string,
+// which is how web-tree-sitter reads JS strings: they can be used with
+// String.prototype.slice directly. Lone surrogates are passed through
+// unchanged and count as one unit.
 export interface AstNode {
   kind: string;
   start: number;
@@ -7,7 +17,38 @@ export interface AstNode {
   startPosition: { row: number; column: number };
   endPosition: { row: number; column: number };
   text?: string;
+  /** Set when `text` was cut short by the `maxTextLength` option */
+  textTruncated?: boolean;
+  /**
+   * With `includeTextSafety`: whether slicing the source at this leaf's
+   * offsets yields its exact text. False when the range splits a surrogate
+   * pair or covers bytes that didn't decode (see `parseBytes()`).
+   */
+  textSafe?: boolean;
   isNamed: boolean;
+  /** Underlying grammar rule, set only when the node's kind is an alias */
+  grammarName?: string;
+  /** Pre-order index within this parse result (root = 0) */
+  id?: number;
+  /**
+   * tree-sitter's internal node id. Only meaningful within the tree this
+   * node came from (e.g. to match it against query captures on that tree);
+   * ids are reused and reshuffled by any reparse.
+   */
+  tsId?: number;
+  /** Field this node occupies in its parent (e.g. `name`, `body`), if any */
+  fieldName?: string;
+  /** Numeric id of `fieldName`, as numbered by `listFields()` */
+  fieldId?: number;
+  /** Set when some of this node's children were cut by `maxOutputSize` */
+  truncated?: boolean;
+  /**
+   * Set when `start === end`: MISSING nodes inserted by error recovery and
+   * empty tokens. Renderers drawing highlight spans usually skip these.
+   */
+  zeroWidth?: boolean;
+  /** With `lineTextLength`: the source line this node starts on */
+  lineText?: string;
   children: AstNode[];
 }
 
@@ -15,25 +56,261 @@ export interface ParseResult {
   success: boolean;
   ast?: AstNode;
   error?: string;
+  /** Machine-readable error category, when the failure has one */
+  errorKind?: 'InputTooLarge';
   language: string;
+  /** Time spent in tree-sitter's parse, when `measureTime` is set */
+  parseMicros?: number;
+  /** Set when the AST was cut short to fit `maxOutputSize` */
+  truncated?: boolean;
+  /** Set when `normalizeLineEndings` rewrote `\r\n` or `\r` in the input */
+  lineEndingsNormalized?: boolean;
+  /** Set when `trimTrailingNewline` removed a final newline */
+  trailingNewlineTrimmed?: boolean;
 }
 
-// Supported languages and their WASM file paths
-const LANGUAGE_WASM: Record<string, string> = {
-  json: '/parsers/tree-sitter-json.wasm',
-  rust: '/parsers/tree-sitter-rust.wasm',
-  javascript: '/parsers/tree-sitter-javascript.wasm',
-  typescript: '/parsers/tree-sitter-typescript.wasm',
-  tsx: '/parsers/tree-sitter-tsx.wasm',
-  python: '/parsers/tree-sitter-python.wasm',
-  go: '/parsers/tree-sitter-go.wasm',
-  ocaml: '/parsers/tree-sitter-ocaml.wasm',
+/** What a `ParseOptions.filter` predicate sees for each node */
+export interface NodeFilterInfo {
+  kind: string;
+  start: number;
+  end: number;
+  depth: number;
+}
+
+export interface DiffUpdateResult extends ParseResult {
+  /** Ranges whose syntactic structure changed between the two versions */
+  changedRanges: ChangedRange[];
+}
+
+export interface DualParseResult extends Omit<ParseResult, 'ast'> {
+  /** The tree with anonymous nodes dropped */
+  named?: AstNode;
+  /** The full tree, from the same parse */
+  full?: AstNode;
+}
+
+export interface ParseOptions {
+  /** Measure the tree-sitter parse call and report it as `parseMicros` */
+  measureTime?: boolean;
+  /**
+   * Sort each node's children by start offset. A no-op for well-formed trees,
+   * but guarantees non-decreasing offsets around extras and error recovery.
+   */
+  sortChildren?: boolean;
+  /** Drop anonymous nodes (punctuation, operators, keywords) */
+  namedOnly?: boolean;
+  /** Report `grammarName` on aliased nodes */
+  includeGrammarNames?: boolean;
+  /**
+   * Rename kinds in the output, e.g. `{ string_literal: 'string' }` to share
+   * one vocabulary across languages or keep a renamed kind stable across a
+   * grammar update. Unmapped kinds pass through; `filter` still sees the
+   * grammar's kinds.
+   */
+  kindAliases?: Record<string, string>;
+  /**
+   * Include a node (and descend into it) only when this returns true. Called
+   * once per candidate child, so a rejected subtree costs a single call. The
+   * root is always included.
+   */
+  filter?: (node: NodeFilterInfo) => boolean;
+  /** Omit named nodes shorter than this many characters (0 keeps all) */
+  minLength?: number;
+  /** Number nodes in pre-order as `id`, stable for this parse result */
+  includeIds?: boolean;
+  /** Report tree-sitter's own node id as `tsId` */
+  includeTsIds?: boolean;
+  /** Report `textSafe` on leaves */
+  includeTextSafety?: boolean;
+  /** Report `fieldName`/`fieldId` on nodes that fill a parent's field */
+  includeFields?: boolean;
+  /**
+   * Skip the grammar's wrapper node (`source_file`, `program`, ...) when it
+   * has exactly one named child, returning that child as the root.
+   */
+  excludeRoot?: boolean;
+  /**
+   * Set to false to leave `text` off every node, for callers that already
+   * have the source and only want structure. Skips reading each leaf's
+   * text out of the tree, which adds up on large files.
+   */
+  includeText?: boolean;
+  /**
+   * Replace leaf text with a one-line display preview: whitespace runs are
+   * collapsed to single spaces and the result is cut to this many characters
+   * with an ellipsis. Offsets are untouched, so exact text is still
+   * available by slicing the source.
+   */
+  textPreviewLength?: number;
+  /**
+   * Attach to every node the text of the line it starts on, cut to this
+   * many characters, so an exported tree can show context without the
+   * source file. Needs the source, so only applies where the caller's code
+   * is known (`parse()` and friends, or `toAst()` given `source`).
+   */
+  lineTextLength?: number;
+  /**
+   * Cut leaf text longer than this many characters (never inside a
+   * surrogate pair) and flag it `textTruncated`, so one huge literal can't
+   * dominate the payload. `start`/`end` still cover the full token.
+   */
+  maxTextLength?: number;
+  /**
+   * Approximate budget, in characters of JSON, for the AST. Conversion stops
+   * once the estimate passes it: the remaining children of the node being
+   * converted are dropped and the node, and the result, are marked
+   * `truncated`. Protects the heap from trees far larger than expected.
+   */
+  maxOutputSize?: number;
+  /**
+   * Convert `\r\n` and lone `\r` to `\n` before parsing (in `parse()`).
+   * Offsets then refer to the normalized text, not the caller's string:
+   * leave this off when original offsets are needed.
+   */
+  normalizeLineEndings?: boolean;
+  /**
+   * Drop a single trailing `\n` (or `\r\n`) before parsing (in `parse()`),
+   * so files differing only by a final newline produce identical trees.
+   * Earlier offsets are unaffected.
+   */
+  trimTrailingNewline?: boolean;
+  /**
+   * Leave a leading `#!` line out of the parse (in `parse()`), keeping
+   * original offsets. On by default only for Haskell, whose grammar has no
+   * shebang rule: JS/TS, Rust and OCaml parse it as a dedicated node, and in
+   * Python and Elixir it's a comment.
+   */
+  skipShebang?: boolean;
+}
+
+// Pre-order counter for `AstNode.id`, reset at the start of each conversion
+let nextNodeId = 0;
+
+// Remaining `maxOutputSize` budget and whether it ran out, per conversion
+let outputBudget = Infinity;
+let outputTruncated = false;
+
+// Source split into lines for `lineTextLength`, per conversion
+let sourceLines: string[] | null = null;
+
+// Rough JSON size of a node's fixed fields (keys, positions, flags)
+const NODE_SIZE_ESTIMATE = 130;
+
+// Default cap on input length (in UTF-16 code units), see setMaxInputLength()
+const DEFAULT_MAX_INPUT_LENGTH = 50 * 1024 * 1024;
+
+// Scaffolding used by parseExpression() to turn a bare expression into a
+// complete program: the fragment is placed between [prefix, suffix].
+const EXPRESSION_SCAFFOLDS: Record<string, [string, string]> = {
+  json: ['', ''],
+  json5: ['', ''],
+  rust: ['fn __f() { ', '; }'],
+  javascript: ['(', ');'],
+  typescript: ['(', ');'],
+  tsx: ['(', ');'],
+  python: ['_ = (', ')'],
+  go: ['package p\nvar _ = ', '\n'],
+  ocaml: ['let _ = (', ')'],
+  haskell: ['_x = (', ')'],
+  zig: ['const _x = ', ';'],
+  elixir: ['', ''],
+  gotmpl: ['{{ ', ' }}'],
+  nix: ['', ''],
+  r: ['', ''],
 };
 
+// Grammars that choke on a `#!` first line, see ParseOptions.skipShebang
+const SHEBANG_SKIP_LANGUAGES = new Set(['haskell']);
+
+/** The range after a leading shebang line, or null if there is none. */
+function afterShebang(code: string): Parser.Range | null {
+  if (!code.startsWith('#!')) return null;
+  const newline = code.indexOf('\n');
+  const startIndex = newline === -1 ? code.length : newline + 1;
+  return {
+    startIndex,
+    endIndex: code.length,
+    startPosition: pointAt(code, startIndex),
+    endPosition: pointAt(code, code.length),
+  };
+}
+
+// Pseudo-languages parse() accepts without a grammar, see plainTextAst()
+const PLAIN_TEXT_LANGUAGES = new Set(['text', 'plaintext', 'txt']);
+
+/** A single `text` node spanning the whole input. */
+function plainTextAst(code: string): AstNode {
+  return {
+    kind: 'text',
+    start: 0,
+    end: code.length,
+    startPosition: { row: 0, column: 0 },
+    endPosition: pointAt(code, code.length),
+    text: code,
+    isNamed: true,
+    children: [],
+  };
+}
+
+function isHighSurrogate(code: number): boolean {
+  return code >= 0xd800 && code <= 0xdbff;
+}
+
+function isLowSurrogate(code: number): boolean {
+  return code >= 0xdc00 && code <= 0xdfff;
+}
+
+/** Whether `text` was cut from a string in the middle of a surrogate pair. */
+function splitsSurrogatePair(text: string): boolean {
+  return text.length > 0 &&
+    (isLowSurrogate(text.charCodeAt(0)) || isHighSurrogate(text.charCodeAt(text.length - 1)));
+}
+
+function truncateText(text: string, maxLength: number): string {
+  let cut = maxLength;
+  // Keep surrogate pairs whole
+  if (cut > 0 && isHighSurrogate(text.charCodeAt(cut - 1))) cut--;
+  return text.slice(0, cut);
+}
+
+function textPreview(text: string, maxLength: number): string {
+  const collapsed = text.replace(/\s+/g, ' ');
+  return collapsed.length > maxLength
+    ? truncateText(collapsed, maxLength) + '…'
+    : collapsed;
+}
+
+/**
+ * Map an AST parsed with `prefix` prepended back onto the original fragment.
+ * Columns only shift on the prefix's last line.
+ */
+function unshiftAst(node: AstNode, prefix: string): AstNode {
+  const rows = prefix.split('\n').length - 1;
+  const columns = prefix.length - (prefix.lastIndexOf('\n') + 1);
+  const mapPoint = (p: { row: number; column: number }) =>
+    p.row === rows
+      ? { row: 0, column: p.column - columns }
+      : { row: p.row - rows, column: p.column };
+
+  const walk = (n: AstNode): AstNode => ({
+    ...n,
+    start: n.start - prefix.length,
+    end: n.end - prefix.length,
+    startPosition: mapPoint(n.startPosition),
+    endPosition: mapPoint(n.endPosition),
+    children: n.children.map(walk),
+  });
+
+  return walk(node);
+}
+
 class TreeSitterParser {
   private parser: Parser | null = null;
   private languages: Map<string, Parser.Language> = new Map();
+  // Names added at runtime by registerLanguage(), lowercase
+  private registered: Set<string> = new Set();
   private initPromise: Promise<void> | null = null;
+  private maxInputLength = DEFAULT_MAX_INPUT_LENGTH;
 
   async init(): Promise<void> {
     if (this.parser) return;
@@ -54,34 +331,129 @@ class TreeSitterParser {
     return this.initPromise;
   }
 
-  async loadLanguage(lang: string): Promise<Parser.Language> {
+  async loadLanguage(name: string): Promise<Parser.Language> {
     await this.init();
 
+    const custom = typeof name === 'string' ? name.trim().toLowerCase() : '';
+    const lang = resolveLanguage(name) ?? (this.registered.has(custom) ? custom : null);
+    if (!lang) {
+      throw new Error(`Unsupported language: ${name}`);
+    }
+
     if (this.languages.has(lang)) {
       return this.languages.get(lang)!;
     }
 
     const wasmPath = LANGUAGE_WASM[lang];
-    if (!wasmPath) {
-      throw new Error(`Unsupported language: ${lang}`);
-    }
 
     const language = await Parser.Language.load(wasmPath);
     this.languages.set(lang, language);
     return language;
   }
 
-  private nodeToAst(node: Parser.SyntaxNode, source: string): AstNode {
+  /**
+   * Make a grammar we don't bundle available under `name`, from its compiled
+   * WASM (as produced by `tree-sitter build --wasm`). The grammar must be
+   * built for an ABI version this web-tree-sitter release supports; an
+   * incompatible one is rejected here rather than on first parse. Grammar
+   * WASM runs with the same privileges as the page, so only load trusted
+   * files. Registered languages aren't listed by getSupportedLanguages().
+   */
+  async registerLanguage(name: string, wasm: Uint8Array): Promise<void> {
+    await this.init();
+
+    const key = name.trim().toLowerCase();
+    if (resolveLanguage(key)) {
+      throw new Error(`Language is already bundled: ${name}`);
+    }
+
+    let language: Parser.Language;
+    try {
+      language = await Parser.Language.load(wasm);
+      // setLanguage() is where tree-sitter checks the ABI version
+      const probe = new Parser();
+      try {
+        probe.setLanguage(language);
+      } finally {
+        probe.delete();
+      }
+    } catch (error) {
+      const reason = error instanceof Error ? error.message : String(error);
+      throw new Error(`Could not load grammar for ${name}: ${reason}`);
+    }
+
+    this.languages.set(key, language);
+    this.registered.add(key);
+  }
+
+  /** Whether a child node survives the output filters in `options`. */
+  private includeChild(
+    child: Parser.SyntaxNode,
+    options: ParseOptions,
+    depth: number,
+  ): boolean {
+    if (options.namedOnly && !child.isNamed) return false;
+    if (
+      options.minLength &&
+      child.isNamed &&
+      child.endIndex - child.startIndex < options.minLength
+    ) {
+      return false;
+    }
+    if (options.filter) {
+      return options.filter({
+        kind: child.type,
+        start: child.startIndex,
+        end: child.endIndex,
+        depth,
+      });
+    }
+    return true;
+  }
+
+  private nodeToAst(
+    node: Parser.SyntaxNode,
+    options: ParseOptions = {},
+    depth = 0,
+    fieldName: string | null = null,
+    source?: string,
+  ): AstNode {
+    if (depth === 0) {
+      nextNodeId = 0;
+      outputBudget = options.maxOutputSize ?? Infinity;
+      outputTruncated = false;
+      sourceLines = source !== undefined && options.lineTextLength !== undefined
+        ? source.split('\n')
+        : null;
+    }
+    const id = options.includeIds ? nextNodeId++ : undefined;
+    const withText = node.childCount === 0 && options.includeText !== false;
+    outputBudget -= NODE_SIZE_ESTIMATE + node.type.length +
+      (withText ? node.endIndex - node.startIndex : 0);
+
     const children: AstNode[] = [];
+    let truncated = false;
     for (let i = 0; i < node.childCount; i++) {
+      if (outputBudget <= 0) {
+        truncated = true;
+        outputTruncated = true;
+        break;
+      }
       const child = node.child(i);
-      if (child) {
-        children.push(this.nodeToAst(child, source));
+      if (child && this.includeChild(child, options, depth + 1)) {
+        const field = options.includeFields ? node.fieldNameForChild(i) : null;
+        children.push(this.nodeToAst(child, options, depth + 1, field));
       }
     }
+    if (options.sortChildren) {
+      children.sort((a, b) => a.start - b.start);
+    }
 
-    return {
-      kind: node.type,
+    const aliases = options.kindAliases;
+    const ast: AstNode = {
+      kind: aliases && Object.prototype.hasOwnProperty.call(aliases, node.type)
+        ? aliases[node.type]
+        : node.type,
       start: node.startIndex,
       end: node.endIndex,
       startPosition: {
@@ -92,13 +464,189 @@ class TreeSitterParser {
         row: node.endPosition.row,
         column: node.endPosition.column,
       },
-      text: node.childCount === 0 ? node.text : undefined,
+      text: withText ? node.text : undefined,
       isNamed: node.isNamed,
       children,
     };
+    if (id !== undefined) {
+      ast.id = id;
+    }
+    if (options.includeTsIds) {
+      ast.tsId = node.id;
+    }
+    if (truncated) {
+      ast.truncated = true;
+    }
+    if (node.startIndex === node.endIndex) {
+      ast.zeroWidth = true;
+    }
+    if (fieldName) {
+      ast.fieldName = fieldName;
+      const fieldId = node.tree.language.fieldIdForName(fieldName);
+      if (fieldId !== null) ast.fieldId = fieldId;
+    }
+    if (ast.text !== undefined && options.includeTextSafety) {
+      ast.textSafe = !splitsSurrogatePair(ast.text);
+    }
+    if (
+      ast.text !== undefined &&
+      options.maxTextLength !== undefined &&
+      ast.text.length > options.maxTextLength
+    ) {
+      ast.text = truncateText(ast.text, options.maxTextLength);
+      ast.textTruncated = true;
+    }
+    if (ast.text !== undefined && options.textPreviewLength !== undefined) {
+      ast.text = textPreview(ast.text, options.textPreviewLength);
+    }
+    if (options.includeGrammarNames && node.grammarType !== node.type) {
+      ast.grammarName = node.grammarType;
+    }
+    if (sourceLines && options.lineTextLength !== undefined) {
+      const line = sourceLines[node.startPosition.row] ?? '';
+      ast.lineText = truncateText(line.replace(/\r$/, ''), options.lineTextLength);
+    }
+
+    if (depth === 0 && options.excludeRoot) {
+      const named = children.filter((child) => child.isNamed);
+      if (named.length === 1) {
+        // Renumber from 0 so `id` still puts the returned root at 0
+        const offset = named[0].id;
+        if (offset !== undefined) {
+          walkAst(named[0], (n) => {
+            n.id! -= offset;
+          });
+        }
+        return named[0];
+      }
+    }
+
+    return ast;
+  }
+
+  /**
+   * Convert a tree parsed elsewhere (e.g. by an `IncrementalParser`) with the
+   * same options `parse()` accepts. Pass the tree's `source` to enable
+   * `lineTextLength`.
+   */
+  toAst(tree: Parser.Tree, options: ParseOptions = {}, source?: string): AstNode {
+    return this.nodeToAst(tree.rootNode, options, 0, null, source);
+  }
+
+  /**
+   * Set the largest input (in UTF-16 code units) the parser will accept.
+   * Larger inputs fail fast instead of exhausting WASM memory.
+   */
+  setMaxInputLength(length: number): void {
+    this.maxInputLength = length;
+  }
+
+  /**
+   * The error `parse()` reports for `code` over the input length limit, or
+   * null when it fits. For callers running their own tree-sitter parser.
+   */
+  inputTooLargeError(code: string): string | null {
+    return code.length > this.maxInputLength
+      ? `Input too large: ${code.length} characters exceeds limit of ${this.maxInputLength}`
+      : null;
+  }
+
+  private async parseTree(
+    code: string,
+    language: string,
+    includedRanges?: Parser.Range[],
+  ): Promise<Parser.Tree> {
+    const tooLarge = this.inputTooLargeError(code);
+    if (tooLarge) {
+      throw new Error(tooLarge);
+    }
+
+    await this.init();
+
+    if (!this.parser) {
+      throw new Error('Parser not initialized');
+    }
+
+    const lang = await this.loadLanguage(language);
+    this.parser.setLanguage(lang);
+    return includedRanges
+      ? this.parser.parse(code, null, { includedRanges })
+      : this.parser.parse(code);
+  }
+
+  /**
+   * Parse `code` and hand the raw tree-sitter tree to `fn`. The tree is
+   * freed once `fn` returns, so don't hold on to nodes from it.
+   */
+  async withTree<T>(
+    code: string,
+    language: string,
+    fn: (tree: Parser.Tree) => T,
+  ): Promise<T> {
+    const tree = await this.parseTree(code, language);
+    try {
+      return fn(tree);
+    } finally {
+      tree.delete();
+    }
   }
 
-  async parse(code: string, language: string): Promise<ParseResult> {
+  /**
+   * Parse `code` into an AST. Never throws or rejects: every failure, including
+   * non-string input from untyped callers and trees too deep to convert, is
+   * reported as `success: false`.
+   *
+   * The pseudo-languages `text`, `plaintext` and `txt` succeed with a single
+   * `text` node holding the whole input, so pipelines scanning mixed files
+   * get one shape for everything.
+   */
+  async parse(
+    code: string,
+    language: string,
+    options: ParseOptions = {},
+  ): Promise<ParseResult> {
+    if (typeof code !== 'string') {
+      return {
+        success: false,
+        error: `Expected source code as a string, got ${typeof code}`,
+        language,
+      };
+    }
+
+    const tooLarge = this.inputTooLargeError(code);
+    if (tooLarge) {
+      return {
+        success: false,
+        error: tooLarge,
+        errorKind: 'InputTooLarge',
+        language,
+      };
+    }
+
+    const normalize = options.normalizeLineEndings && code.includes('\r');
+    if (normalize) {
+      code = code.replace(/\r\n?/g, '\n');
+    }
+    const lineEndingsNormalized = normalize || undefined;
+
+    const trim = options.trimTrailingNewline && code.endsWith('\n');
+    if (trim) {
+      code = code.slice(0, code.endsWith('\r\n') ? -2 : -1);
+    }
+    const trailingNewlineTrimmed = trim || undefined;
+
+    // Matched like resolveLanguage(): case-insensitive, ignoring whitespace
+    if (typeof language === 'string' && PLAIN_TEXT_LANGUAGES.has(language.trim().toLowerCase())) {
+      return {
+        success: true,
+        ast: plainTextAst(code),
+        language,
+        lineEndingsNormalized,
+        trailingNewlineTrimmed,
+      };
+    }
+
+    let tree: Parser.Tree | null = null;
     try {
       await this.init();
 
@@ -113,8 +661,268 @@ class TreeSitterParser {
       const lang = await this.loadLanguage(language);
       this.parser.setLanguage(lang);
 
-      const tree = this.parser.parse(code);
-      const ast = this.nodeToAst(tree.rootNode, code);
+      const skipShebang = options.skipShebang ??
+        SHEBANG_SKIP_LANGUAGES.has(resolveLanguage(language) ?? language);
+      const body = skipShebang ? afterShebang(code) : null;
+
+      const startedAt = options.measureTime ? performance.now() : 0;
+      tree = body
+        ? this.parser.parse(code, null, { includedRanges: [body] })
+        : this.parser.parse(code);
+      const parseMicros = options.measureTime
+        ? Math.round((performance.now() - startedAt) * 1000)
+        : undefined;
+      const ast = this.nodeToAst(tree.rootNode, options, 0, null, code);
+
+      return {
+        success: true,
+        ast,
+        language,
+        parseMicros,
+        truncated: outputTruncated || undefined,
+        lineEndingsNormalized,
+        trailingNewlineTrimmed,
+      };
+    } catch (error) {
+      return {
+        success: false,
+        error: error instanceof RangeError && /call stack/i.test(error.message)
+          ? 'Syntax tree too deeply nested to convert'
+          : error instanceof Error ? error.message : String(error),
+        language,
+      };
+    } finally {
+      // Free the WASM-side tree so repeated calls don't grow the heap
+      tree?.delete();
+    }
+  }
+
+  /**
+   * Parse once and convert the tree twice, named-only and full, so a UI can
+   * toggle between structural and detailed views without reparsing.
+   * `namedOnly` in `options` is ignored.
+   */
+  async parseDual(
+    code: string,
+    language: string,
+    options: ParseOptions = {},
+  ): Promise<DualParseResult> {
+    try {
+      const tree = await this.parseTree(code, language);
+      try {
+        const named = this.nodeToAst(tree.rootNode, { ...options, namedOnly: true }, 0, null, code);
+        const namedTruncated = outputTruncated;
+        const full = this.nodeToAst(tree.rootNode, { ...options, namedOnly: false }, 0, null, code);
+        return {
+          success: true,
+          named,
+          full,
+          language,
+          truncated: namedTruncated || outputTruncated || undefined,
+        };
+      } finally {
+        tree.delete();
+      }
+    } catch (error) {
+      return {
+        success: false,
+        error: error instanceof Error ? error.message : String(error),
+        language,
+      };
+    }
+  }
+
+  /**
+   * Parse `newCode` incrementally from `oldCode`'s tree and report which
+   * ranges changed structurally. The text delta between the versions is
+   * applied as an edit, so only the affected region is reparsed.
+   */
+  async parseDiffUpdate(
+    oldCode: string,
+    newCode: string,
+    language: string,
+    options: ParseOptions = {},
+  ): Promise<DiffUpdateResult> {
+    const tooLarge = this.inputTooLargeError(oldCode) ?? this.inputTooLargeError(newCode);
+    if (tooLarge) {
+      return {
+        success: false,
+        error: tooLarge,
+        errorKind: 'InputTooLarge',
+        language,
+        changedRanges: [],
+      };
+    }
+
+    let oldTree: Parser.Tree | null = null;
+    let newTree: Parser.Tree | null = null;
+    try {
+      oldTree = await this.parseTree(oldCode, language);
+      // A concurrent call may have switched the shared parser to another
+      // grammar while parseTree() was resuming
+      this.parser!.setLanguage(oldTree.language);
+      oldTree.edit(computeEdit(oldCode, newCode));
+      newTree = this.parser!.parse(newCode, oldTree);
+
+      const changedRanges = oldTree.getChangedRanges(newTree).map(toChangedRange);
+      const ast = this.nodeToAst(newTree.rootNode, options, 0, null, newCode);
+
+      return {
+        success: true,
+        ast,
+        language,
+        truncated: outputTruncated || undefined,
+        changedRanges,
+      };
+    } catch (error) {
+      return {
+        success: false,
+        error: error instanceof Error ? error.message : String(error),
+        language,
+        changedRanges: [],
+      };
+    } finally {
+      oldTree?.delete();
+      newTree?.delete();
+    }
+  }
+
+  /**
+   * Parse raw bytes in the given encoding, e.g. `windows-1252` for legacy
+   * latin-1 files that can't be passed as a string without lossy conversion.
+   * Offsets refer to the decoded string. If the bytes aren't valid in the
+   * encoding, leaves covering invalid sequences have no `text`.
+   */
+  async parseBytes(
+    bytes: Uint8Array,
+    language: string,
+    encoding = 'utf-8',
+    options: ParseOptions = {},
+  ): Promise<ParseResult> {
+    let decoder: TextDecoder;
+    try {
+      decoder = new TextDecoder(encoding, { fatal: true });
+    } catch {
+      return { success: false, error: `Unsupported encoding: ${encoding}`, language };
+    }
+
+    let code: string;
+    let lossy = false;
+    try {
+      code = decoder.decode(bytes);
+    } catch {
+      code = new TextDecoder(encoding).decode(bytes);
+      lossy = true;
+    }
+
+    const result = await this.parse(code, language, options);
+    if (lossy && result.ast) {
+      walkAst(result.ast, (node) => {
+        if (!node.text?.includes('\uFFFD')) return;
+        node.text = undefined;
+        if (options.includeTextSafety) node.textSafe = false;
+      });
+    }
+    return result;
+  }
+
+  /**
+   * Parse only `ranges` of `code`, as if they were one contiguous document,
+   * e.g. code embedded in another language. Offsets refer to the full `code`.
+   */
+  async parseRanges(
+    code: string,
+    language: string,
+    ranges: Parser.Range[],
+    options: ParseOptions = {},
+  ): Promise<ParseResult> {
+    try {
+      const tree = await this.parseTree(code, language, ranges);
+      try {
+        const ast = this.nodeToAst(tree.rootNode, options, 0, null, code);
+        return { success: true, ast, language, truncated: outputTruncated || undefined };
+      } finally {
+        tree.delete();
+      }
+    } catch (error) {
+      return {
+        success: false,
+        error: error instanceof Error ? error.message : String(error),
+        language,
+      };
+    }
+  }
+
+  /**
+   * Parse and return the tree re-rooted at the first node of `kind`, e.g. the
+   * first `class_definition`. Offsets still refer to the full `code`.
+   */
+  async parseRootedAtKind(
+    code: string,
+    language: string,
+    kind: string,
+    options: ParseOptions = {},
+  ): Promise<ParseResult> {
+    const result = await this.parse(code, language, options);
+    if (!result.ast) return result;
+
+    const root = firstNodeByKind(result.ast, kind);
+    if (!root) {
+      return {
+        success: false,
+        error: `No ${kind} node found`,
+        language,
+      };
+    }
+    return { ...result, ast: root };
+  }
+
+  /**
+   * Parse a fragment that is a single expression rather than a whole file
+   * (e.g. REPL input). The fragment is wrapped in minimal scaffolding so the
+   * grammar's start rule accepts it, and the returned AST is the expression's
+   * subtree with offsets relative to `code`. Fails when the fragment isn't
+   * exactly one expression, and when it has syntax errors (with the
+   * expression's `ast` still attached).
+   */
+  async parseExpression(code: string, language: string): Promise<ParseResult> {
+    const scaffold = EXPRESSION_SCAFFOLDS[resolveLanguage(language) ?? language];
+    if (!scaffold) {
+      return {
+        success: false,
+        error: `Expression parsing not supported for: ${language}`,
+        language,
+      };
+    }
+
+    const inner = code.trim();
+    if (!inner) {
+      return { success: false, error: 'Empty expression', language };
+    }
+
+    const [prefix, suffix] = scaffold;
+    let tree: Parser.Tree | null = null;
+    try {
+      tree = await this.parseTree(prefix + code + suffix, language);
+
+      const start = prefix.length + code.length - code.trimStart().length;
+      const node = tree.rootNode.namedDescendantForIndex(start, start + inner.length);
+      // The smallest node covering the fragment is part of the scaffold when
+      // the fragment isn't one expression (e.g. `a; b` or `foo }`)
+      if (node.startIndex < prefix.length || node.endIndex > prefix.length + code.length) {
+        return { success: false, error: 'Not a single expression', language };
+      }
+      // With an empty scaffold that check can't fail: several expressions
+      // (`1\n2` in R, `{} {}` in JSON) are covered by the root itself
+      const root = tree.rootNode;
+      if (node.id === root.id || (!prefix && !suffix && root.namedChildCount > 1)) {
+        return { success: false, error: 'Not a single expression', language };
+      }
+
+      const ast = unshiftAst(this.nodeToAst(node), prefix);
+      if (node.hasError) {
+        return { success: false, ast, error: 'Syntax error in expression', language };
+      }
 
       return {
         success: true,
@@ -127,6 +935,8 @@ class TreeSitterParser {
         error: error instanceof Error ? error.message : String(error),
         language,
       };
+    } finally {
+      tree?.delete();
     }
   }
 
@@ -0,0 +1,73 @@
+import { describe, expect, it, vi } from 'vitest';
+import { runQuery, runQueryStreaming } from './queries';
+
+describe('runQuery', () => {
+  it('returns captures grouped by the pattern that matched', async () => {
+    const code = 'fn a() {}\nlet f = |x| x;';
+    const query = `
+      (function_item name: (identifier) @function.name)
+      (closure_expression) @closure
+    `;
+    const matches = await runQuery(code, 'rust', query);
+    expect(matches).toHaveLength(2);
+    expect(matches[0].patternIndex).toBe(0);
+    expect(matches[0].captures[0]).toMatchObject({ name: 'function.name', kind: 'identifier', text: 'a' });
+    expect(matches[1].patternIndex).toBe(1);
+    expect(matches[1].captures[0]).toMatchObject({ name: 'closure', kind: 'closure_expression', text: '|x| x' });
+  });
+});
+
+describe('runQueryStreaming', () => {
+  const code = 'let a = 1;\nlet b = 2;\nlet c = 3;\n';
+  const query = '(variable_declarator name: (identifier) @name)';
+
+  it('hands each match to the callback in order', async () => {
+    const onMatch = vi.fn();
+    await runQueryStreaming(code, 'javascript', query, onMatch);
+    expect(onMatch).toHaveBeenCalledTimes(3);
+    expect(onMatch.mock.calls.map(([match]) => match.captures[0].text)).toEqual(['a', 'b', 'c']);
+  });
+
+  it('stops once the callback returns false', async () => {
+    const onMatch = vi.fn(() => false);
+    await runQueryStreaming(code, 'javascript', query, onMatch);
+    expect(onMatch).toHaveBeenCalledTimes(1);
+  });
+
+  it('reports a match spanning several top-level constructs once', async () => {
+    const onMatch = vi.fn();
+    await runQueryStreaming(code, 'javascript', '(program) @root', onMatch);
+    expect(onMatch).toHaveBeenCalledTimes(1);
+    expect(onMatch.mock.calls[0][0].captures[0]).toMatchObject({ kind: 'program', start: 0 });
+  });
+
+  it('finds the same matches as a whole-tree search', async () => {
+    const source = 'function a(x) { return x; }\nclass B { m() { a(); } }\nconst c = a() + a();\n';
+    const calls = '(call_expression function: (identifier) @callee)';
+    const onMatch = vi.fn();
+    await runQueryStreaming(source, 'javascript', calls, onMatch);
+    expect(onMatch.mock.calls.map(([match]) => match.captures[0].start)).toEqual(
+      [...source.matchAll(/a\(\)/g)].map((m) => m.index),
+    );
+  });
+
+  it('rejects a query that does not compile', async () => {
+    await expect(runQueryStreaming(code, 'javascript', '(no_such_node) @x', vi.fn())).rejects.toThrow();
+  });
+});
+
+describe('patternIndex', () => {
+  it('tells apart alternatives in one query', async () => {
+    const code = 'function named() {}\nconst arrow = () => {};\nfunction other() {}\n';
+    const query = `
+      (function_declaration name: (identifier) @name)
+      (variable_declarator name: (identifier) @name value: (arrow_function))
+    `;
+    const matches = await runQuery(code, 'javascript', query);
+    expect(matches.map((m) => [m.patternIndex, m.captures[0].text])).toEqual([
+      [0, 'named'],
+      [1, 'arrow'],
+      [0, 'other'],
+    ]);
+  });
+});
@@ -0,0 +1,198 @@
+import type Parser from 'web-tree-sitter';
+import { parser } from './parser';
+import { resolveLanguage } from './languages';
+import { toNodeSpan, type NodeSpan } from './navigation';
+
+// ============================================
+// Types
+// ============================================
+
+export interface Tag extends NodeSpan {
+  /** Symbol name, from the query's `@name` capture */
+  name: string;
+  kind: 'definition' | 'reference';
+  /** e.g. `function`, `class`, `call`, from `@definition.function` */
+  symbolKind: string;
+}
+
+export interface QueryCaptureResult extends NodeSpan {
+  /** Capture name without the `@`, e.g. `function.name` */
+  name: string;
+  text: string;
+}
+
+export interface QueryMatchResult {
+  /** Index of the pattern in the query source that produced this match */
+  patternIndex: number;
+  captures: QueryCaptureResult[];
+}
+
+// ============================================
+// Bundled queries
+// ============================================
+
+// Query files shipped per language (downloaded by scripts/download-parsers.js
+// into /queries/<language>/<name>.scm)
+const BUNDLED_QUERIES: Record<string, string[]> = {
+  json: ['highlights'],
+  rust: ['highlights', 'tags'],
+  javascript: ['highlights', 'tags'],
+  typescript: ['highlights', 'tags'],
+  tsx: ['highlights', 'tags'],
+  python: ['highlights', 'tags'],
+  go: ['highlights', 'tags'],
+  ocaml: ['highlights', 'tags'],
+  haskell: ['highlights'],
+  elixir: ['highlights', 'tags'],
+};
+
+const queryCache = new Map<string, Parser.Query>();
+
+/**
+ * Load and compile one of a language's bundled queries (e.g. `highlights`).
+ * Compiled queries are cached per language.
+ */
+export async function loadBundledQuery(
+  language: string,
+  name: string,
+): Promise<Parser.Query> {
+  const lang = resolveLanguage(language) ?? language;
+  const key = `${lang}/${name}`;
+  const cached = queryCache.get(key);
+  if (cached) return cached;
+
+  if (!BUNDLED_QUERIES[lang]?.includes(name)) {
+    throw new Error(`No ${name} query bundled for: ${language}`);
+  }
+
+  const grammar = await parser.loadLanguage(lang);
+  const response = await fetch(`/queries/${key}.scm`);
+  if (!response.ok) {
+    throw new Error(`Failed to load ${name} query for ${language}: ${response.status}`);
+  }
+
+  const query = grammar.query(await response.text());
+  queryCache.set(key, query);
+  return query;
+}
+
+// ============================================
+// Public API
+// ============================================
+
+/**
+ * List the capture names emitted by a language's highlight query, so a
+ * frontend can build a capture → color theme without hardcoding them.
+ */
+export async function highlightCaptureNames(language: string): Promise<string[]> {
+  const query = await loadBundledQuery(language, 'highlights');
+  return [...query.captureNames];
+}
+
+/**
+ * Tag symbol definitions and references using the grammar's bundled
+ * `tags.scm` query (the same format GitHub's code navigation uses). The span
+ * covers the whole tagged node, e.g. the full function definition.
+ */
+export async function getTags(code: string, language: string): Promise<Tag[]> {
+  const query = await loadBundledQuery(language, 'tags');
+  return parser.withTree(code, language, (tree) => {
+    const tags: Tag[] = [];
+    for (const match of query.matches(tree.rootNode)) {
+      const name = match.captures.find((c) => c.name === 'name');
+      const tagged = match.captures.find(
+        (c) => c.name.startsWith('definition.') || c.name.startsWith('reference.'),
+      );
+      if (!name || !tagged) continue;
+
+      const [kind, symbolKind] = tagged.name.split('.');
+      tags.push({
+        ...toNodeSpan(tagged.node),
+        name: name.node.text,
+        kind: kind as Tag['kind'],
+        symbolKind,
+      });
+    }
+    return tags;
+  });
+}
+
+function toCaptureResult(capture: Parser.QueryCapture): QueryCaptureResult {
+  return {
+    ...toNodeSpan(capture.node),
+    name: capture.name,
+    text: capture.node.text,
+  };
+}
+
+/** Earliest start offset among a match's captures. */
+function matchStart(match: Parser.QueryMatch): number {
+  return Math.min(...match.captures.map((capture) => capture.node.startIndex));
+}
+
+/**
+ * Byte windows splitting the file at each top-level child, so a query can be
+ * run one window at a time. The last window is open-ended.
+ */
+function topLevelWindows(root: Parser.SyntaxNode): [number, number | undefined][] {
+  const starts = [0];
+  for (const child of root.children.slice(1)) {
+    if (child.startIndex > starts[starts.length - 1]) starts.push(child.startIndex);
+  }
+  return starts.map((start, i) => [start, starts[i + 1]]);
+}
+
+/**
+ * Run a query and hand each match to `onMatch` as soon as it's found, e.g.
+ * for a broad query over a huge file. Matching runs one top-level construct
+ * at a time, so only that window's matches are held in memory, and returning
+ * `false` from `onMatch` stops the search itself. Each match is reported
+ * once, in the window where its first capture starts. Throws if the query
+ * doesn't compile.
+ */
+export async function runQueryStreaming(
+  code: string,
+  language: string,
+  source: string,
+  onMatch: (match: QueryMatchResult) => boolean | void,
+): Promise<void> {
+  const grammar = await parser.loadLanguage(language);
+  const query = grammar.query(source);
+  try {
+    await parser.withTree(code, language, (tree) => {
+      for (const [startIndex, endIndex] of topLevelWindows(tree.rootNode)) {
+        for (const match of query.matches(tree.rootNode, { startIndex, endIndex })) {
+          // Matches spanning windows (e.g. on the root) turn up in each of them
+          const start = matchStart(match);
+          if (start < startIndex || (endIndex !== undefined && start >= endIndex)) continue;
+
+          const result = {
+            patternIndex: match.pattern,
+            captures: match.captures.map(toCaptureResult),
+          };
+          if (onMatch(result) === false) return;
+        }
+      }
+    });
+  } finally {
+    query.delete();
+  }
+}
+
+/**
+ * Run a query and collect every match with its captures (name, kind, range,
+ * text), in the order matches are produced, e.g. for a query playground.
+ * Each match keeps the `patternIndex` that produced it. Throws if the query
+ * doesn't compile.
+ */
+export async function runQuery(
+  code: string,
+  language: string,
+  source: string,
+): Promise<QueryMatchResult[]> {
+  const matches: QueryMatchResult[] = [];
+  await runQueryStreaming(code, language, source, (match) => {
+    matches.push(match);
+  });
+  return matches;
+}
@@ -0,0 +1,80 @@
+import type { AstNode, ParseResult } from './parser';
+
+// ============================================
+// ParseResult JSON Schema
+// ============================================
+
+// Mirror of the `AstNode` / `ParseResult` interfaces in parser.ts, for
+// consumers that deserialize parse output in other languages or codegen
+// their own types. The property tables are checked with `satisfies`, so
+// adding or removing an interface field without updating them fails `tsc`.
+// Bump the version on breaking changes; new optional fields are additive.
+
+export const AST_SCHEMA_VERSION = 1;
+
+const POINT = {
+  type: 'object',
+  properties: {
+    row: { type: 'integer', minimum: 0 },
+    column: { type: 'integer', minimum: 0 },
+  },
+  required: ['row', 'column'],
+  additionalProperties: false,
+};
+
+const AST_NODE_PROPERTIES = {
+  kind: { type: 'string' },
+  start: { type: 'integer', minimum: 0 },
+  end: { type: 'integer', minimum: 0 },
+  startPosition: { $ref: '#/$defs/Point' },
+  endPosition: { $ref: '#/$defs/Point' },
+  text: { type: 'string' },
+  textTruncated: { type: 'boolean' },
+  textSafe: { type: 'boolean' },
+  isNamed: { type: 'boolean' },
+  grammarName: { type: 'string' },
+  id: { type: 'integer', minimum: 0 },
+  tsId: { type: 'integer', minimum: 0 },
+  fieldName: { type: 'string' },
+  fieldId: { type: 'integer', minimum: 1 },
+  truncated: { type: 'boolean' },
+  zeroWidth: { type: 'boolean' },
+  lineText: { type: 'string' },
+  children: { type: 'array', items: { $ref: '#/$defs/AstNode' } },
+} satisfies Record<keyof AstNode, unknown>;
+
+const PARSE_RESULT_PROPERTIES = {
+  success: { type: 'boolean' },
+  ast: { $ref: '#/$defs/AstNode' },
+  error: { type: 'string' },
+  errorKind: { enum: ['InputTooLarge'] },
+  language: { type: 'string' },
+  parseMicros: { type: 'integer', minimum: 0 },
+  truncated: { type: 'boolean' },
+  lineEndingsNormalized: { type: 'boolean' },
+  trailingNewlineTrimmed: { type: 'boolean' },
+} satisfies Record<keyof ParseResult, unknown>;
+
+const AST_SCHEMA = {
+  $schema: 'https://json-schema.org/draft/2020-12/schema',
+  $id: `urn:spectree:parse-result:v${AST_SCHEMA_VERSION}`,
+  title: 'ParseResult',
+  type: 'object',
+  properties: PARSE_RESULT_PROPERTIES,
+  required: ['success', 'language'] satisfies (keyof ParseResult)[],
+  $defs: {
+    Point: POINT,
+    AstNode: {
+      type: 'object',
+      properties: AST_NODE_PROPERTIES,
+      required: [
+        'kind', 'start', 'end', 'startPosition', 'endPosition', 'isNamed', 'children',
+      ] satisfies (keyof AstNode)[],
+    },
+  },
+};
+
+/** JSON Schema (draft 2020-12) describing `ParseResult` and `AstNode`. */
+export function getAstSchema(): string {
+  return JSON.stringify(AST_SCHEMA, null, 2);
+}
@@ -0,0 +1,22 @@
+import { describe, expect, it } from 'vitest';
+import { structuralSearch } from './search';
+
+describe('structuralSearch', () => {
+  it('matches by kind and shape with a wildcard', async () => {
+    const code = 'foo(1, 2);\nfoo(x, 3);\nbar(1);\nfoo(1, 2, 3);\n';
+    const found = await structuralSearch(code, 'foo(__, 2)', 'javascript');
+    expect(found.map((range) => code.slice(range.start, range.end))).toEqual(['foo(1, 2)', 'foo(x, 3)']);
+  });
+
+  it('lets a wildcard stand for a whole subtree', async () => {
+    const code = 'f(g(h(1)));\nf(2);\n';
+    const found = await structuralSearch(code, 'f(__)', 'javascript');
+    expect(found.map((range) => code.slice(range.start, range.end))).toEqual(['f(g(h(1)))', 'g(h(1))', 'h(1)', 'f(2)']);
+  });
+
+  it('accepts bare expression patterns in Rust', async () => {
+    const code = 'fn f(x: i32) -> i32 {\n    let y = x + g(1);\n    y\n}\n';
+    const found = await structuralSearch(code, 'x + __', 'rust');
+    expect(found.map((range) => code.slice(range.start, range.end))).toEqual(['x + g(1)']);
+  });
+});
@@ -0,0 +1,92 @@
+import { parser, type AstNode } from './parser';
+import { firstNodeByKind, walkAst } from './ast-utils';
+import type { ChangedRange } from './edits';
+
+// ============================================
+// Structural search
+// ============================================
+
+// A pattern is an example snippet, e.g. `foo(__, 1)`. Nodes match by kind
+// and shape (named nodes only, leaf text ignored), and a leaf spelled `__`
+// matches any subtree.
+
+const WILDCARD = '__';
+
+function isWildcard(node: AstNode): boolean {
+  return node.children.length === 0 && node.text === WILDCARD;
+}
+
+function matches(pattern: AstNode, node: AstNode): boolean {
+  if (isWildcard(pattern)) return true;
+  if (pattern.kind !== node.kind || pattern.children.length !== node.children.length) {
+    return false;
+  }
+  return pattern.children.every((child, i) => matches(child, node.children[i]));
+}
+
+function dropAnonymous(node: AstNode): AstNode {
+  return {
+    ...node,
+    children: node.children.filter((child) => child.isNamed).map(dropAnonymous),
+  };
+}
+
+/**
+ * Strip wrapper nodes (`program`, `expression_statement`, ...) that cover
+ * exactly the same text as their only child.
+ */
+function patternRoot(ast: AstNode): AstNode {
+  let node = ast;
+  while (node.children.length === 1) {
+    const [child] = node.children;
+    if (isWildcard(child) || child.start !== node.start || child.end !== node.end) break;
+    node = child;
+  }
+  return node;
+}
+
+async function parsePattern(pattern: string, language: string): Promise<AstNode> {
+  const source = pattern.trim();
+  const result = await parser.parse(source, language, { namedOnly: true });
+  if (result.ast && !firstNodeByKind(result.ast, 'ERROR')) {
+    return patternRoot(result.ast);
+  }
+
+  // Not a valid program on its own (e.g. a bare expression in Rust)
+  const expression = await parser.parseExpression(source, language);
+  if (!expression.ast) {
+    throw new Error(result.error ?? expression.error ?? 'Invalid pattern');
+  }
+  return patternRoot(dropAnonymous(expression.ast));
+}
+
+/**
+ * Find every place in `code` shaped like the `pattern` snippet: same node
+ * kinds and structure, with `__` as a wildcard for any subtree. A friendlier
+ * alternative to writing a query for simple searches. Nested matches are
+ * all reported, outermost first.
+ */
+export async function structuralSearch(
+  code: string,
+  pattern: string,
+  language: string,
+): Promise<ChangedRange[]> {
+  const template = await parsePattern(pattern, language);
+  const result = await parser.parse(code, language, { namedOnly: true });
+  if (!result.ast) {
+    throw new Error(result.error ?? 'Parse failed');
+  }
+
+  const found: ChangedRange[] = [];
+  walkAst(result.ast, (node) => {
+    if (matches(template, node)) {
+      found.push({
+        start: node.start,
+        end: node.end,
+        startPosition: node.startPosition,
+        endPosition: node.endPosition,
+      });
+    }
+  });
+  return found;
+}
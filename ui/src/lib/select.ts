@@ -0,0 +1,96 @@
+import type { AstNode } from './parser';
+
+// ============================================
+// Selector parsing
+// ============================================
+
+// A selector like `function_item > identifier` is a list of kind steps, each
+// related to the previous one by a combinator:
+//   A B    B is a descendant of A
+//   A > B  B is a direct child of A
+// `*` matches any kind.
+
+interface SelectorStep {
+  kind: string;
+  combinator: 'descendant' | 'child';
+}
+
+function parseSelector(selector: string): SelectorStep[] {
+  const tokens = selector.replace(/>/g, ' > ').trim().split(/\s+/);
+  const steps: SelectorStep[] = [];
+  let combinator: SelectorStep['combinator'] = 'descendant';
+
+  for (const token of tokens) {
+    if (token === '>') {
+      if (steps.length === 0 || combinator === 'child') {
+        throw new Error(`Invalid selector: ${selector}`);
+      }
+      combinator = 'child';
+      continue;
+    }
+    steps.push({ kind: token, combinator });
+    combinator = 'descendant';
+  }
+
+  if (steps.length === 0 || combinator === 'child') {
+    throw new Error(`Invalid selector: ${selector}`);
+  }
+  return steps;
+}
+
+// ============================================
+// Matching
+// ============================================
+
+function kindMatches(step: SelectorStep, node: AstNode): boolean {
+  return step.kind === '*' || step.kind === node.kind;
+}
+
+/**
+ * Check that steps[0..=i] match the path ending at path[pos], given that
+ * steps[i] already matches path[pos].
+ */
+function chainMatches(steps: SelectorStep[], i: number, path: AstNode[], pos: number): boolean {
+  if (i === 0) return true;
+
+  const prev = steps[i - 1];
+  if (steps[i].combinator === 'child') {
+    return pos > 0 && kindMatches(prev, path[pos - 1]) && chainMatches(steps, i - 1, path, pos - 1);
+  }
+
+  for (let p = pos - 1; p >= 0; p--) {
+    if (kindMatches(prev, path[p]) && chainMatches(steps, i - 1, path, p)) {
+      return true;
+    }
+  }
+  return false;
+}
+
+// ============================================
+// Public API
+// ============================================
+
+/**
+ * Select nodes with a small CSS-like selector over node kinds, e.g.
+ * `function_item > identifier` or `class_definition identifier`.
+ * A gentler alternative to tree-sitter queries for simple structural
+ * lookups. Matches are returned in document order.
+ */
+export function select(ast: AstNode, selector: string): AstNode[] {
+  const steps = parseSelector(selector);
+  const last = steps.length - 1;
+  const matches: AstNode[] = [];
+  const path: AstNode[] = [];
+
+  const visit = (node: AstNode) => {
+    path.push(node);
+    if (kindMatches(steps[last], node) && chainMatches(steps, last, path, path.length - 1)) {
+      matches.push(node);
+    }
+    node.children.forEach(visit);
+    path.pop();
+  };
+
+  visit(ast);
+  return matches;
+}
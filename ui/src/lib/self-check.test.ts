@@ -0,0 +1,12 @@
+import { describe, expect, it } from 'vitest';
+import { parser } from './parser';
+import { selfCheck } from './self-check';
+
+describe('selfCheck', () => {
+  it('round-trips a snippet in every supported language', async () => {
+    const report = await selfCheck();
+    expect(report.languages.map((entry) => entry.language)).toEqual(parser.getSupportedLanguages());
+    expect(report.languages.filter((entry) => !entry.ok)).toEqual([]);
+    expect(report.ok).toBe(true);
+  });
+});
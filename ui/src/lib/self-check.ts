@@ -0,0 +1,92 @@
+import { parser } from './parser';
+import { reconstructSource } from './ast-utils';
+
+// ============================================
+// Self-check snippets
+// ============================================
+
+// One small, valid program per supported language. Every language returned
+// by getSupportedLanguages() needs an entry here.
+const SNIPPETS: Record<string, string> = {
+  json: '{"name": "spectree", "tags": [1, 2.5, true, null]}',
+  json5: '{\n  // comment\n  name: "spectree",\n  tags: [1, 2,],\n}',
+  rust: 'fn add(a: i32, b: i32) -> i32 { a + b }',
+  javascript: 'function add(a, b) { return a + b; }',
+  typescript: 'function add(a: number, b: number): number { return a + b; }',
+  tsx: 'const App = () => <div className="app">{1 + 2}</div>;',
+  python: 'def add(a, b):\n    return a + b',
+  go: 'package main\n\nfunc add(a int, b int) int { return a + b }',
+  ocaml: 'let add a b = a + b',
+  haskell: 'add :: Int -> Int -> Int\nadd a b = a + b',
+  zig: 'pub fn add(a: i32, b: i32) i32 {\n    return a + b;\n}',
+  elixir: 'defmodule Math do\n  def add(a, b), do: a + b\nend',
+  gotmpl: '{{ range .Items }}<li>{{ .Name }}</li>{{ end }}',
+  markdown: '# Title\n\nSome text.\n\n```rust\nfn main() {}\n```\n',
+  proto: 'syntax = "proto3";\n\nmessage User {\n  string name = 1;\n  Role role = 2;\n}\n\nenum Role {\n  ROLE_UNSPECIFIED = 0;\n}\n',
+  nix: 'let\n  add = a: b: a + b;\nin {\n  sum = add 1 2;\n  inherit add;\n}\n',
+  r: 'add <- function(a, b) {\n  a + b\n}\n\nc(1, 2, 3) |> sum()\n',
+};
+
+// ============================================
+// Public API
+// ============================================
+
+export interface SelfCheckEntry {
+  language: string;
+  ok: boolean;
+  error?: string;
+}
+
+export interface SelfCheckReport {
+  ok: boolean;
+  languages: SelfCheckEntry[];
+}
+
+async function checkLanguage(language: string): Promise<SelfCheckEntry> {
+  const snippet = SNIPPETS[language];
+  if (snippet === undefined) {
+    return { language, ok: false, error: 'No self-check snippet' };
+  }
+
+  const result = await parser.parse(snippet, language);
+  if (!result.ast) {
+    return { language, ok: false, error: result.error };
+  }
+
+  const hasError = await parser.withTree(snippet, language, (tree) => tree.rootNode.hasError);
+  if (hasError) {
+    return { language, ok: false, error: 'Snippet parsed with errors' };
+  }
+
+  const { text } = reconstructSource(result.ast, snippet);
+  if (text.trim() !== snippet.trim()) {
+    return { language, ok: false, error: 'Leaf text does not round-trip the source' };
+  }
+
+  return { language, ok: true };
+}
+
+/**
+ * Parse a canonical snippet in every supported language and verify it parses
+ * cleanly and round-trips through leaf text. Catches missing or ABI-mismatched
+ * grammar files in a deployed bundle with a single call.
+ */
+export async function selfCheck(): Promise<SelfCheckReport> {
+  const languages: SelfCheckEntry[] = [];
+  for (const language of parser.getSupportedLanguages()) {
+    try {
+      languages.push(await checkLanguage(language));
+    } catch (error) {
+      languages.push({
+        language,
+        ok: false,
+        error: error instanceof Error ? error.message : String(error),
+      });
+    }
+  }
+
+  return {
+    ok: languages.every((entry) => entry.ok),
+    languages,
+  };
+}
@@ -0,0 +1,64 @@
+import { describe, expect, it } from 'vitest';
+import { parser } from './parser';
+import { computeEdit } from './edits';
+import { createTree, deleteTree, editTree, reparse, treeAst } from './sessions';
+
+describe('tree sessions', () => {
+  it('creates, edits, reparses and deletes a tree', async () => {
+    const before = 'let a = 1;\nlet b = 2;\n';
+    const after = 'let a = 1;\nlet b = 20;\n';
+
+    const created = await createTree(before, 'javascript');
+    expect(created.success).toBe(true);
+    const handle = created.handle!;
+    expect(created.ast).toEqual((await parser.parse(before, 'javascript')).ast);
+
+    editTree(handle, computeEdit(before, after));
+    const update = await reparse(handle, after);
+    expect(update).toMatchObject({ success: true, language: 'javascript' });
+    expect(update).not.toHaveProperty('ast');
+    expect(update.changedRanges.length).toBeGreaterThan(0);
+    expect(update.changedRanges.every((range) => range.start >= before.indexOf('let b'))).toBe(true);
+
+    expect(treeAst(handle).ast).toEqual((await parser.parse(after, 'javascript')).ast);
+
+    deleteTree(handle);
+    expect(() => treeAst(handle)).toThrow(`Unknown tree handle: ${handle}`);
+  });
+
+  it('finds the edit by diffing when none was reported', async () => {
+    const { handle } = await createTree('[1, 2]', 'json');
+    try {
+      const update = await reparse(handle!, '[1, 2, 3]');
+      expect(update.success).toBe(true);
+      expect(treeAst(handle!).ast).toEqual((await parser.parse('[1, 2, 3]', 'json')).ast);
+    } finally {
+      deleteTree(handle!);
+    }
+  });
+
+  it('throws for unknown handles', async () => {
+    expect(() => editTree(-1, computeEdit('a', 'b'))).toThrow('Unknown tree handle: -1');
+    await expect(reparse(-1, 'a')).rejects.toThrow('Unknown tree handle: -1');
+    expect(() => deleteTree(-1)).not.toThrow();
+  });
+
+  it('applies the input length limit', async () => {
+    const { handle } = await createTree('[1]', 'json');
+    parser.setMaxInputLength(8);
+    try {
+      expect(await createTree('[1, 2, 3, 4]', 'json')).toMatchObject({
+        success: false,
+        errorKind: 'InputTooLarge',
+      });
+      expect(await reparse(handle!, '[1, 2, 3, 4]')).toMatchObject({
+        success: false,
+        errorKind: 'InputTooLarge',
+        changedRanges: [],
+      });
+    } finally {
+      parser.setMaxInputLength(50 * 1024 * 1024);
+      deleteTree(handle!);
+    }
+  });
+});
@@ -0,0 +1,105 @@
+import type Parser from 'web-tree-sitter';
+import { parser, type ParseOptions, type ParseResult } from './parser';
+import type { ChangedRange } from './edits';
+import { IncrementalParser } from './incremental';
+
+// ============================================
+// Types
+// ============================================
+
+export interface TreeSessionResult extends ParseResult {
+  /** Handle for `editTree()`/`reparse()`, set when the first parse succeeded */
+  handle?: number;
+}
+
+export interface TreeUpdateResult extends Omit<ParseResult, 'ast'> {
+  /** Ranges the edit touched or whose syntax changed; empty on failure */
+  changedRanges: ChangedRange[];
+}
+
+// ============================================
+// Session registry
+// ============================================
+
+// Live trees by handle. Each owns a tree-sitter parser and tree in WASM
+// memory until `deleteTree()`.
+const sessions = new Map<number, IncrementalParser>();
+let nextHandle = 1;
+
+function session(handle: number): IncrementalParser {
+  const found = sessions.get(handle);
+  if (!found) throw new Error(`Unknown tree handle: ${handle}`);
+  return found;
+}
+
+// Sessions parse with their own tree-sitter parser, so apply the shared
+// parser's input length limit here
+function tooLarge(code: string, language: string): ParseResult | null {
+  const error = parser.inputTooLargeError(code);
+  return error ? { success: false, error, errorKind: 'InputTooLarge', language } : null;
+}
+
+// ============================================
+// Public API
+// ============================================
+
+/**
+ * Parse `code` and keep its tree alive behind a numeric handle, for
+ * callers that can only pass plain values (e.g. across `postMessage`).
+ * Later `reparse()` calls reuse the tree, so a keystroke costs work
+ * proportional to the edit rather than the file. Release it with
+ * `deleteTree()`.
+ */
+export async function createTree(
+  code: string,
+  language: string,
+  options: ParseOptions = {},
+): Promise<TreeSessionResult> {
+  const rejected = tooLarge(code, language);
+  if (rejected) return rejected;
+
+  const incremental = new IncrementalParser(language);
+  const result = await incremental.parse(code, options);
+  if (!result.success) {
+    incremental.delete();
+    return result;
+  }
+  const handle = nextHandle++;
+  sessions.set(handle, incremental);
+  return { ...result, handle };
+}
+
+/**
+ * Report an edit to the handle's text ahead of `reparse()`. Optional: without
+ * it the edit is found by diffing against the previous text. When used, it
+ * must cover every change since the last parse. Throws for unknown handles.
+ */
+export function editTree(handle: number, edit: Parser.Edit): void {
+  session(handle).edit(edit);
+}
+
+/**
+ * Reparse the handle's tree against `code`, reusing everything the edit
+ * didn't touch, and report the changed ranges. No AST is built, so the cost
+ * stays proportional to the edit; call `treeAst()` for one. Throws for
+ * unknown handles.
+ */
+export async function reparse(handle: number, code: string): Promise<TreeUpdateResult> {
+  const incremental = session(handle);
+  const rejected = tooLarge(code, incremental.language);
+  if (rejected) return { ...rejected, changedRanges: [] };
+
+  const result = await incremental.update(code);
+  return { ...result, changedRanges: result.success ? incremental.changedRanges() : [] };
+}
+
+/** Convert the handle's current tree to an AST. Throws for unknown handles. */
+export function treeAst(handle: number, options: ParseOptions = {}): ParseResult {
+  return session(handle).currentAst(options);
+}
+
+/** Free a handle's parser and tree. Unknown handles are ignored. */
+export function deleteTree(handle: number): void {
+  sessions.get(handle)?.delete();
+  sessions.delete(handle);
+}
@@ -0,0 +1,41 @@
+import { describe, expect, it } from 'vitest';
+import { canonicalSexp } from './sexp';
+
+describe('canonicalSexp', () => {
+  it('prints named nodes one per line with field labels', async () => {
+    const sexp = await canonicalSexp('def f(x):\n    return x\n', 'python');
+    expect(sexp).toBe([
+      '(module',
+      '  (function_definition',
+      '    name: (identifier)',
+      '    parameters: (parameters',
+      '      (identifier))',
+      '    body: (block',
+      '      (return_statement',
+      '        (identifier)))))',
+    ].join('\n'));
+  });
+
+  it('ignores formatting', async () => {
+    const a = await canonicalSexp('def f(x):\n    return x\n', 'python');
+    const b = await canonicalSexp('def f( x ) :\n\n        return   x', 'python');
+    expect(a).toBe(b);
+  });
+
+  it('prints operators with includeAnonymous', async () => {
+    const sexp = await canonicalSexp('a + b', 'javascript', { includeAnonymous: true });
+    expect(sexp).toContain('left: (identifier)');
+    expect(sexp).toContain('"+"');
+    expect(sexp).toContain('right: (identifier)');
+  });
+
+  it('leaves anonymous nodes out by default', async () => {
+    const sexp = await canonicalSexp('a + b', 'javascript');
+    expect(sexp).not.toContain('"+"');
+  });
+
+  it('marks missing anonymous tokens', async () => {
+    const sexp = await canonicalSexp('foo(1', 'javascript', { includeAnonymous: true });
+    expect(sexp).toContain('(MISSING ")")');
+  });
+});
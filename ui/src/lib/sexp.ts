@@ -0,0 +1,71 @@
+import type Parser from 'web-tree-sitter';
+import { parser } from './parser';
+
+// ============================================
+// Types
+// ============================================
+
+export interface SexpOptions {
+  /**
+   * Print anonymous tokens (operators, punctuation, keywords) inline as
+   * quoted strings, e.g. `left: (identifier)` `"+"` `right: (identifier)`,
+   * to compare against the tree-sitter playground.
+   */
+  includeAnonymous?: boolean;
+}
+
+// ============================================
+// Canonical S-expressions
+// ============================================
+
+function writeNode(
+  cursor: Parser.TreeCursor,
+  depth: number,
+  lines: string[],
+  options: SexpOptions,
+): void {
+  const field = cursor.currentFieldName;
+  const prefix = `${'  '.repeat(depth)}${field ? `${field}: ` : ''}`;
+  if (!cursor.nodeIsNamed) {
+    // A closer inserted by error recovery, like `tree-sitter parse` prints it
+    const token = JSON.stringify(cursor.nodeType);
+    lines.push(`${prefix}${cursor.nodeIsMissing ? `(MISSING ${token})` : token}`);
+    return;
+  }
+
+  const label = cursor.nodeIsMissing ? `MISSING ${cursor.nodeType}` : cursor.nodeType;
+  lines.push(`${prefix}(${label}`);
+
+  if (cursor.gotoFirstChild()) {
+    do {
+      if (cursor.nodeIsNamed || options.includeAnonymous) {
+        writeNode(cursor, depth + 1, lines, options);
+      }
+    } while (cursor.gotoNextSibling());
+    cursor.gotoParent();
+  }
+
+  lines[lines.length - 1] += ')';
+}
+
+/**
+ * Render named nodes as an indented S-expression, one node per line with
+ * `field:` labels, like `tree-sitter parse` prints. Stable enough to commit
+ * as a golden file and review as a diff when a grammar is bumped.
+ */
+export async function canonicalSexp(
+  code: string,
+  language: string,
+  options: SexpOptions = {},
+): Promise<string> {
+  return parser.withTree(code, language, (tree) => {
+    const lines: string[] = [];
+    const cursor = tree.walk();
+    try {
+      writeNode(cursor, 0, lines, options);
+    } finally {
+      cursor.delete();
+    }
+    return lines.join('\n');
+  });
+}
@@ -0,0 +1,62 @@
+import { describe, expect, it } from 'vitest';
+import { splitSfc } from './sfc';
+
+const blockText = (code: string, kind: 'vue' | 'svelte') =>
+  splitSfc(code, kind).map((block) => [block.tag, code.slice(block.contentStart, block.contentEnd)]);
+
+describe('splitSfc', () => {
+  it('splits a Vue component into its blocks', () => {
+    const code = [
+      '<template><div v-if="ok"><template v-if="x">a</template></div></template>',
+      '<script setup lang="ts">const ok = true;</script>',
+      '<style lang="scss">div { color: red; }</style>',
+    ].join('\n');
+    const blocks = splitSfc(code, 'vue');
+    expect(blocks.map((block) => [block.tag, block.lang])).toEqual([
+      ['template', undefined],
+      ['script', 'ts'],
+      ['style', 'scss'],
+    ]);
+    expect(code.slice(blocks[0].contentStart, blocks[0].contentEnd)).toBe(
+      '<div v-if="ok"><template v-if="x">a</template></div>',
+    );
+    expect(code.slice(blocks[1].start, blocks[1].end)).toBe(
+      '<script setup lang="ts">const ok = true;</script>',
+    );
+  });
+
+  it('skips blocks inside comments', () => {
+    const code = '<!-- <script>old()</script> -->\n<script>current()</script>\n';
+    expect(blockText(code, 'vue')).toEqual([['script', 'current()']]);
+  });
+
+  it('stops at an unterminated comment', () => {
+    const code = '<script>a()</script>\n<!-- <style>p {}</style>\n<script>b()</script>\n';
+    expect(blockText(code, 'vue')).toEqual([['script', 'a()']]);
+  });
+
+  it('treats self-closing blocks as empty', () => {
+    const code = '<script src="./main.ts" lang="ts" />\n<template><p /></template>\n<style>p {}</style>\n';
+    const blocks = splitSfc(code, 'vue');
+    expect(blocks.map((block) => block.tag)).toEqual(['script', 'template', 'style']);
+    expect(blocks[0]).toMatchObject({ lang: 'ts', start: 0, end: 36, contentStart: 36, contentEnd: 36 });
+    expect(blockText(code, 'vue').slice(1)).toEqual([['template', '<p />'], ['style', 'p {}']]);
+  });
+
+  it('does not count a self-closing nested template as an opener', () => {
+    const code = '<template><template v-slot:empty /><b>x</b></template>\n<script>s()</script>\n';
+    expect(blockText(code, 'vue')).toEqual([
+      ['template', '<template v-slot:empty /><b>x</b>'],
+      ['script', 's()'],
+    ]);
+  });
+
+  it('treats markup outside script and style as Svelte templates', () => {
+    const code = '<script>let n = 0;</script>\n\n<button>{n}</button>\n\n<style>button {}</style>\n';
+    expect(blockText(code, 'svelte')).toEqual([
+      ['script', 'let n = 0;'],
+      ['template', '<button>{n}</button>'],
+      ['style', 'button {}'],
+    ]);
+  });
+});
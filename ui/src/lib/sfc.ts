@@ -0,0 +1,123 @@
+// ============================================
+// Single-file component splitter
+// ============================================
+
+// Vue and Svelte components mix markup, script and style in one file. Rather
+// than bundling dedicated grammars, we locate the top-level blocks with a
+// simple scan so each region can be parsed with the matching language.
+
+export type SfcKind = 'vue' | 'svelte';
+
+export interface SfcBlock {
+  tag: 'template' | 'script' | 'style';
+  /** Value of the block's `lang` attribute, e.g. `ts` or `scss` */
+  lang?: string;
+  /** Range of the whole block, including its tags */
+  start: number;
+  end: number;
+  /** Range of the block's content, between the tags */
+  contentStart: number;
+  contentEnd: number;
+}
+
+const OPEN_TAG = /<(template|script|style)(\s[^>]*)?>|<!--/g;
+
+function langAttribute(attrs: string | undefined): string | undefined {
+  return attrs?.match(/\blang\s*=\s*["']?([\w-]+)/)?.[1];
+}
+
+function isSelfClosing(openTag: string): boolean {
+  return openTag.endsWith('/>');
+}
+
+/**
+ * Find the end of a block's content: the index of its matching close tag.
+ * Nested tags of the same name (e.g. `<template v-if>` inside a Vue
+ * template) are balanced.
+ */
+function findClose(code: string, tag: string, from: number): number {
+  const pattern = new RegExp(`<${tag}(\\s[^>]*)?>|</${tag}\\s*>`, 'g');
+  pattern.lastIndex = from;
+  let depth = 1;
+  for (let m = pattern.exec(code); m; m = pattern.exec(code)) {
+    if (m[0].startsWith('</')) {
+      depth--;
+      if (depth === 0) return m.index;
+    } else if (tag === 'template' && !isSelfClosing(m[0])) {
+      depth++;
+    }
+  }
+  return -1;
+}
+
+/** Markup outside script/style blocks becomes Svelte's template regions. */
+function svelteTemplates(code: string, blocks: SfcBlock[]): SfcBlock[] {
+  const templates: SfcBlock[] = [];
+  let cursor = 0;
+  for (const gapEnd of [...blocks.map((b) => b.start), code.length]) {
+    const segment = code.slice(cursor, gapEnd);
+    if (segment.trim()) {
+      const start = cursor + segment.length - segment.trimStart().length;
+      const end = cursor + segment.trimEnd().length;
+      templates.push({ tag: 'template', start, end, contentStart: start, contentEnd: end });
+    }
+    const block = blocks.find((b) => b.start === gapEnd);
+    cursor = block ? block.end : gapEnd;
+  }
+  return templates;
+}
+
+/**
+ * Split a `.vue` or `.svelte` file into its top-level template, script and
+ * style blocks, in source order. Unclosed blocks are skipped, self-closing
+ * ones (`<script src="./main.ts" />`) have empty content, and an unterminated
+ * `<!--` comments out the rest of the file.
+ */
+export function splitSfc(code: string, kind: SfcKind): SfcBlock[] {
+  const blocks: SfcBlock[] = [];
+  OPEN_TAG.lastIndex = 0;
+
+  for (let m = OPEN_TAG.exec(code); m; m = OPEN_TAG.exec(code)) {
+    if (m[0] === '<!--') {
+      const close = code.indexOf('-->', m.index);
+      if (close === -1) break;
+      OPEN_TAG.lastIndex = close + 3;
+      continue;
+    }
+
+    const tag = m[1] as SfcBlock['tag'];
+    if (kind === 'svelte' && tag === 'template') continue;
+
+    const contentStart = m.index + m[0].length;
+    if (isSelfClosing(m[0])) {
+      blocks.push({
+        tag,
+        lang: langAttribute(m[2]),
+        start: m.index,
+        end: contentStart,
+        contentStart,
+        contentEnd: contentStart,
+      });
+      continue;
+    }
+
+    const contentEnd = findClose(code, tag, contentStart);
+    if (contentEnd === -1) continue;
+
+    const end = code.indexOf('>', contentEnd) + 1;
+    blocks.push({
+      tag,
+      lang: langAttribute(m[2]),
+      start: m.index,
+      end,
+      contentStart,
+      contentEnd,
+    });
+    OPEN_TAG.lastIndex = end;
+  }
+
+  if (kind === 'svelte') {
+    return [...blocks, ...svelteTemplates(code, blocks)].sort((a, b) => a.start - b.start);
+  }
+  return blocks;
+}
@@ -0,0 +1,47 @@
+import { describe, expect, it } from 'vitest';
+import { indexDeclarations } from './symbols';
+
+describe('indexDeclarations', () => {
+  it('indexes Rust items and use declarations', async () => {
+    const code = [
+      'use std::collections::HashMap;',
+      'use crate::util;',
+      '',
+      'struct Cache { map: HashMap<String, u32> }',
+      '',
+      'fn lookup(key: &str) -> u32 { 0 }',
+      '',
+    ].join('\n');
+    const { symbols, imports } = await indexDeclarations(code, 'rust');
+    expect(symbols.map(({ kind, name }) => [kind, name])).toEqual([
+      ['struct_item', 'Cache'],
+      ['function_item', 'lookup'],
+    ]);
+    expect(imports.map((entry) => entry.modulePath)).toEqual(['std::collections::HashMap', 'crate::util']);
+  });
+
+  it('indexes Python definitions and imports', async () => {
+    const code = [
+      'import os',
+      'from typing import List',
+      '',
+      'class Repo:',
+      '    def load(self):',
+      '        pass',
+      '',
+      'def main():',
+      '    pass',
+      '',
+    ].join('\n');
+    const { symbols, imports } = await indexDeclarations(code, 'python');
+    expect(symbols.map(({ kind, name }) => [kind, name])).toEqual([
+      ['class_definition', 'Repo'],
+      ['function_definition', 'load'],
+      ['function_definition', 'main'],
+    ]);
+    expect(imports.map(({ kind, modulePath }) => [kind, modulePath])).toEqual([
+      ['import_statement', 'os'],
+      ['import_from_statement', 'typing'],
+    ]);
+  });
+});
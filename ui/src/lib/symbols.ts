@@ -0,0 +1,195 @@
+import type Parser from 'web-tree-sitter';
+import { parser } from './parser';
+import { kindsFor } from './kinds';
+import { resolveLanguage } from './languages';
+import { toNodeSpan, type NodeSpan } from './navigation';
+
+// ============================================
+// Types
+// ============================================
+
+export interface ImportEntry extends NodeSpan {
+  /** Full source text of the import statement */
+  text: string;
+  /** Imported module or path, when it can be extracted */
+  modulePath?: string;
+}
+
+export interface DeclarationEntry extends NodeSpan {
+  /** Text of the declaration's `name` field */
+  name: string;
+}
+
+export interface FunctionSignature extends NodeSpan {
+  /** Text of the function's `name` field, if it has one */
+  name?: string;
+  /** Source from the start of the function up to its body, trimmed */
+  signature: string;
+}
+
+export interface DeclarationIndex {
+  symbols: DeclarationEntry[];
+  imports: ImportEntry[];
+}
+
+// ============================================
+// Helpers
+// ============================================
+
+// Fields grammars use for the imported module, most specific first
+const MODULE_FIELDS = ['source', 'path', 'module_name', 'argument', 'module', 'name'];
+
+function unquote(text: string): string {
+  return text.replace(/^(["'`])(.*)\1$/s, '$2');
+}
+
+function modulePathOf(node: Parser.SyntaxNode): string | undefined {
+  for (const field of MODULE_FIELDS) {
+    const child = node.childForFieldName(field);
+    if (child) return unquote(child.text);
+  }
+  return undefined;
+}
+
+function toImportEntry(node: Parser.SyntaxNode): ImportEntry {
+  return {
+    ...toNodeSpan(node),
+    text: node.text,
+    modulePath: modulePathOf(node),
+  };
+}
+
+// Languages whose docstring is a string literal opening the body
+const BODY_DOCSTRING_LANGUAGES = new Set(['python']);
+
+function isDocComment(node: Parser.SyntaxNode, language: string, comments: string[]): boolean {
+  if (!comments.includes(node.type)) return false;
+  // Plain `//` comments in Rust are not documentation
+  if (language === 'rust') return /^(\/\/\/(?!\/)|\/\*\*(?!\*))/.test(node.text);
+  return true;
+}
+
+/** The leading string statement of a definition's body, unquoted. */
+function bodyDocstring(node: Parser.SyntaxNode): string | null {
+  const first = node.childForFieldName('body')?.firstNamedChild;
+  const literal = first?.type === 'expression_statement' ? first.firstNamedChild : null;
+  if (literal?.type !== 'string') return null;
+  return literal.text.replace(/^[a-zA-Z]*("""|'''|"|')([\s\S]*)\1$/, '$2');
+}
+
+/** Doc comments directly above a definition, skipping Rust attributes. */
+function leadingComments(
+  node: Parser.SyntaxNode,
+  language: string,
+  comments: string[],
+): string | null {
+  const lines: string[] = [];
+  let below = node;
+  for (let sibling = node.previousSibling; sibling; sibling = sibling.previousSibling) {
+    // A blank line separates the comment from the definition
+    if (sibling.endPosition.row < below.startPosition.row - 1) break;
+    if (sibling.type === 'attribute_item') {
+      below = sibling;
+      continue;
+    }
+    if (!isDocComment(sibling, language, comments)) break;
+    lines.unshift(sibling.text.trimEnd());
+    below = sibling;
+  }
+  return lines.length > 0 ? lines.join('\n') : null;
+}
+
+function collectImports(root: Parser.SyntaxNode, language: string): ImportEntry[] {
+  const { imports } = kindsFor(language);
+  if (imports.length === 0) return [];
+  return root.descendantsOfType(imports).map(toImportEntry);
+}
+
+// ============================================
+// Public API
+// ============================================
+
+/**
+ * List a file's import/dependency statements (`use` in Rust, `import` in
+ * Python/JS, import specs in Go, ...), in document order. Input for building
+ * an import graph without learning each grammar.
+ */
+export async function extractImports(code: string, language: string): Promise<ImportEntry[]> {
+  return parser.withTree(code, language, (tree) => collectImports(tree.rootNode, language));
+}
+
+/**
+ * Declarations and imports of a file from a single traversal, for
+ * repo-wide indexers. Declarations without a `name` field (e.g. anonymous
+ * classes) are skipped.
+ */
+export async function indexDeclarations(code: string, language: string): Promise<DeclarationIndex> {
+  const { declarations, imports } = kindsFor(language);
+  const importKinds = new Set(imports);
+
+  return parser.withTree(code, language, (tree) => {
+    const index: DeclarationIndex = { symbols: [], imports: [] };
+    for (const node of tree.rootNode.descendantsOfType([...declarations, ...imports])) {
+      if (importKinds.has(node.type)) {
+        index.imports.push(toImportEntry(node));
+        continue;
+      }
+      const name = node.childForFieldName('name');
+      if (name) index.symbols.push({ ...toNodeSpan(node), name: name.text });
+    }
+    return index;
+  });
+}
+
+/**
+ * The header of every function, i.e. its source up to the `body` field,
+ * like `fn foo(x: i32) -> i32`, for hovers and outlines. Functions without
+ * a `body` field (e.g. declarations without a definition) are skipped.
+ */
+export async function functionSignatures(
+  code: string,
+  language: string,
+): Promise<FunctionSignature[]> {
+  const { functions } = kindsFor(language);
+  if (functions.length === 0) return [];
+
+  return parser.withTree(code, language, (tree) => {
+    const signatures: FunctionSignature[] = [];
+    for (const fn of tree.rootNode.descendantsOfType(functions)) {
+      const body = fn.childForFieldName('body');
+      if (!body) continue;
+      signatures.push({
+        ...toNodeSpan(fn),
+        name: fn.childForFieldName('name')?.text,
+        signature: code.slice(fn.startIndex, body.startIndex).trim(),
+      });
+    }
+    return signatures;
+  });
+}
+
+/**
+ * Documentation of the function or class enclosing `offset`: the leading
+ * string of its body in Python (without quotes), otherwise the comments
+ * directly above it, verbatim (in Rust, only `///` and `/**` doc
+ * comments). Returns null when the definition has none, or there is no
+ * enclosing definition.
+ */
+export async function getDocstring(
+  code: string,
+  language: string,
+  offset: number,
+): Promise<string | null> {
+  const resolved = resolveLanguage(language) ?? language;
+  const { declarations, functions, comments } = kindsFor(resolved);
+  const definitions = new Set([...declarations, ...functions]);
+
+  return parser.withTree(code, resolved, (tree) => {
+    let node: Parser.SyntaxNode | null = tree.rootNode.descendantForIndex(offset);
+    while (node && !definitions.has(node.type)) node = node.parent;
+    if (!node) return null;
+
+    if (BODY_DOCSTRING_LANGUAGES.has(resolved)) return bodyDocstring(node);
+    return leadingComments(node, resolved, comments);
+  });
+}
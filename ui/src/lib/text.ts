@@ -0,0 +1,53 @@
+import { pointAt } from './edits';
+
+// ============================================
+// Source text helpers
+// ============================================
+
+export interface SourceLine {
+  /** 0-indexed line number */
+  line: number;
+  /** The line's text, without its `\n` or `\r\n` terminator */
+  text: string;
+}
+
+/**
+ * The full source line containing `offset`, e.g. for error displays and
+ * hover previews. Offsets past the end resolve to the last line.
+ */
+export function lineTextForOffset(code: string, offset: number): SourceLine {
+  const clamped = Math.max(0, Math.min(offset, code.length));
+  // An offset on a `\n` belongs to the line that newline terminates
+  const lineStart = clamped === 0 ? 0 : code.lastIndexOf('\n', clamped - 1) + 1;
+  const newline = code.indexOf('\n', clamped);
+  const lineEnd = newline === -1 ? code.length : newline;
+
+  const text = code.slice(lineStart, lineEnd);
+  return {
+    line: pointAt(code, lineStart).row,
+    text: text.endsWith('\r') ? text.slice(0, -1) : text,
+  };
+}
+
+/**
+ * Whether `offset` is a valid place to slice `code`: within `[0, length]`
+ * and not between the two halves of a surrogate pair. Offsets computed by
+ * hand (e.g. from byte counts or grapheme math) can land inside a pair,
+ * and slicing there leaves a lone surrogate.
+ */
+export function isCharBoundary(code: string, offset: number): boolean {
+  if (!Number.isInteger(offset) || offset < 0 || offset > code.length) return false;
+  if (offset === 0 || offset === code.length) return true;
+  const before = code.charCodeAt(offset - 1);
+  const after = code.charCodeAt(offset);
+  return !(before >= 0xd800 && before <= 0xdbff && after >= 0xdc00 && after <= 0xdfff);
+}
+
+/**
+ * Snap `offset` to a valid boundary: clamped into `[0, length]`, and moved
+ * back to the pair's start when it falls inside a surrogate pair.
+ */
+export function roundToCharBoundary(code: string, offset: number): number {
+  const clamped = Math.max(0, Math.min(Math.trunc(offset) || 0, code.length));
+  return isCharBoundary(code, clamped) ? clamped : clamped - 1;
+}
@@ -0,0 +1,33 @@
+import { readFileSync } from 'node:fs';
+import { join } from 'node:path';
+import { fileURLToPath } from 'node:url';
+import Parser from 'web-tree-sitter';
+import { parser } from '../src/lib/parser';
+
+// The library loads tree-sitter.wasm, grammars and query files from
+// site-absolute URLs (`/parsers/...`, `/queries/...`). Under Node there is no
+// dev server, so serve those paths from public/ instead.
+const publicDir = fileURLToPath(new URL('../public', import.meta.url));
+const fromPublic = (url: string) => join(publicDir, url);
+
+const init = Parser.init.bind(Parser);
+Parser.init = (moduleOptions?: object) =>
+  init({ ...moduleOptions, locateFile: (scriptName: string) => fromPublic(scriptName) });
+
+// Parser.Language only exists once init() has run
+await parser.init();
+const load = Parser.Language.load.bind(Parser.Language);
+Parser.Language.load = (input: string | Uint8Array) =>
+  load(typeof input === 'string' ? fromPublic(input) : input);
+
+const realFetch = globalThis.fetch;
+globalThis.fetch = async (input: RequestInfo | URL, requestInit?: RequestInit) => {
+  if (typeof input === 'string' && input.startsWith('/')) {
+    try {
+      return new Response(readFileSync(fromPublic(input)));
+    } catch {
+      return new Response(null, { status: 404 });
+    }
+  }
+  return realFetch(input, requestInit);
+};
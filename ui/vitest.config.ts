@@ -0,0 +1,10 @@
+import { defineConfig } from 'vitest/config';
+
+// Tests run in Node against the grammar WASMs in public/parsers, so
+// `npm run download-parsers` must have been run first.
+export default defineConfig({
+  test: {
+    include: ['src/**/*.test.ts'],
+    setupFiles: ['test/setup.ts'],
+  },
+});